@@ -0,0 +1,16 @@
+use egui::Ui;
+
+use crate::app::App;
+
+pub fn add(app: &mut App, ui: &mut Ui) {
+    ui.vertical_centered(|ui| {
+        ui.add_space(ui.available_height() / 3.);
+        ui.spinner();
+        ui.label("Waiting for the process to launch...");
+        if ui.button("Cancel").clicked() {
+            // Stopping a pending attach cancels it, the process exits.
+            let result = app.target.process().stop();
+            app.notifications.report("Failed to cancel attach", result);
+        }
+    });
+}
@@ -1,6 +1,7 @@
 use egui::{widgets::global_dark_light_mode_switch, Align, Layout, RichText, Ui};
 use lldb::SBDebugger;
 
+use crate::app::components::notification_history_button;
 use crate::app::App;
 
 pub fn add(app: &mut App, ui: &mut Ui) {
@@ -31,6 +32,8 @@ pub fn add(app: &mut App, ui: &mut Ui) {
         ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
             global_dark_light_mode_switch(ui);
             ui.separator();
+            notification_history_button(app, ui);
+            ui.separator();
             ui.label(RichText::new(SBDebugger::version()).small());
         });
     });
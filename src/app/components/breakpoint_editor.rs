@@ -0,0 +1,248 @@
+use std::path::PathBuf;
+
+use egui::{Context, TextEdit};
+use lldb::{SBBreakpoint, SBBreakpointLocation};
+
+use crate::app::snapshot::BreakpointInfo;
+use crate::app::App;
+use crate::debugger::{
+    self, BreakpointCommands, BreakpointOptions, BreakpointSpec, ScriptLanguage,
+};
+
+/// The breakpoint the editor changes.
+enum EditedBreakpoint {
+    Existing(SBBreakpoint),
+    /// A logpoint that is only created once the editor is applied, so
+    /// cancelling doesn't leave a breakpoint behind.
+    New(BreakpointSpec),
+}
+
+/// Editable state of the breakpoint editor.
+pub struct BreakpointEditor {
+    breakpoint: EditedBreakpoint,
+    title: String,
+    enabled: bool,
+    condition: String,
+    ignore_count: String,
+    one_shot: bool,
+    thread_id: String,
+    thread_name: String,
+    queue_name: String,
+    /// Turns the breakpoint into a logpoint if set.
+    log_message: String,
+    /// Commands executed when the breakpoint is hit.
+    commands: BreakpointCommands,
+    /// The locations with a label and if they are enabled.
+    locations: Vec<(SBBreakpointLocation, String, bool)>,
+    error: Option<String>,
+}
+
+impl BreakpointEditor {
+    pub fn new(breakpoint: &BreakpointInfo) -> Self {
+        let options = &breakpoint.options;
+        Self {
+            breakpoint: EditedBreakpoint::Existing(breakpoint.breakpoint.clone()),
+            title: format!("Breakpoint {}: {}", breakpoint.id, breakpoint.spec),
+            enabled: options.enabled,
+            condition: options.condition.clone().unwrap_or_default(),
+            ignore_count: options.ignore_count.to_string(),
+            one_shot: options.one_shot,
+            thread_id: options
+                .thread_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            thread_name: options.thread_name.clone().unwrap_or_default(),
+            queue_name: options.queue_name.clone().unwrap_or_default(),
+            log_message: breakpoint.log_message.clone().unwrap_or_default(),
+            commands: breakpoint.commands.clone().unwrap_or_default(),
+            locations: breakpoint
+                .locations
+                .iter()
+                .map(|location| {
+                    let mut label = format!("{}.{}", breakpoint.id, location.id);
+                    if let Some(function_name) = &location.function_name {
+                        label.push_str(&format!(" {}", function_name));
+                    }
+                    (location.location.clone(), label, location.enabled)
+                })
+                .collect(),
+            error: None,
+        }
+    }
+
+    /// Opens the editor for a new logpoint at the line of the file.
+    pub fn new_logpoint(path: PathBuf, line: u32) -> Self {
+        Self {
+            title: format!("New logpoint: {}:{}", path.display(), line),
+            breakpoint: EditedBreakpoint::New(BreakpointSpec::Line(path, line)),
+            enabled: true,
+            condition: String::new(),
+            ignore_count: "0".to_string(),
+            one_shot: false,
+            thread_id: String::new(),
+            thread_name: String::new(),
+            queue_name: String::new(),
+            log_message: String::new(),
+            commands: BreakpointCommands::default(),
+            locations: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn options(&self) -> Result<BreakpointOptions, String> {
+        let non_empty = |s: &str| (!s.trim().is_empty()).then(|| s.trim().to_string());
+        let ignore_count = match self.ignore_count.trim() {
+            "" => 0,
+            count => count
+                .parse()
+                .map_err(|_| format!("invalid ignore count: {}", count))?,
+        };
+        let thread_id = non_empty(&self.thread_id)
+            .map(|id| id.parse().map_err(|_| format!("invalid thread id: {}", id)))
+            .transpose()?;
+        Ok(BreakpointOptions {
+            enabled: self.enabled,
+            condition: non_empty(&self.condition),
+            ignore_count,
+            one_shot: self.one_shot,
+            thread_id,
+            thread_name: non_empty(&self.thread_name),
+            queue_name: non_empty(&self.queue_name),
+        })
+    }
+}
+
+pub fn add(app: &mut App, ctx: &Context) {
+    let Some(editor) = &mut app.breakpoint_editor else {
+        return;
+    };
+
+    let mut open = true;
+    let mut apply = false;
+    let mut cancel = false;
+    egui::Window::new(editor.title.clone())
+        .id(egui::Id::new("breakpoint_editor"))
+        .collapsible(false)
+        .resizable(false)
+        .open(&mut open)
+        .show(ctx, |ui| {
+            ui.checkbox(&mut editor.enabled, "Enabled");
+            egui::Grid::new("breakpoint_editor")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Condition:");
+                    ui.add(TextEdit::singleline(&mut editor.condition).hint_text("i == 42"));
+                    ui.end_row();
+
+                    ui.label("Ignore count:");
+                    ui.text_edit_singleline(&mut editor.ignore_count);
+                    ui.end_row();
+
+                    ui.label("Thread ID:");
+                    ui.add(TextEdit::singleline(&mut editor.thread_id).hint_text("any"));
+                    ui.end_row();
+
+                    ui.label("Thread name:");
+                    ui.add(TextEdit::singleline(&mut editor.thread_name).hint_text("any"));
+                    ui.end_row();
+
+                    ui.label("Queue name:");
+                    ui.add(TextEdit::singleline(&mut editor.queue_name).hint_text("any"));
+                    ui.end_row();
+
+                    ui.label("Log message:");
+                    ui.add(
+                        TextEdit::singleline(&mut editor.log_message)
+                            .hint_text("i = {i}, don't stop"),
+                    )
+                    .on_hover_text("log the message with the {expressions} evaluated and continue");
+                    ui.end_row();
+                });
+            ui.checkbox(&mut editor.one_shot, "One-shot")
+                .on_hover_text("delete the breakpoint once it was hit");
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Commands:");
+                let language = &mut editor.commands.language;
+                ui.selectable_value(language, ScriptLanguage::Lldb, "lldb");
+                ui.selectable_value(language, ScriptLanguage::Python, "Python");
+            });
+            let hint = match editor.commands.language {
+                ScriptLanguage::Lldb => "bt\nframe variable",
+                ScriptLanguage::Python => "print(frame.GetFunctionName())",
+            };
+            ui.add(
+                TextEdit::multiline(&mut editor.commands.script)
+                    .code_editor()
+                    .hint_text(hint)
+                    .desired_rows(4),
+            )
+            .on_hover_text("executed by lldb when the breakpoint is hit");
+            if !editor.locations.is_empty() {
+                ui.separator();
+                ui.label("Locations:");
+                for (_, label, enabled) in &mut editor.locations {
+                    ui.checkbox(enabled, label.as_str());
+                }
+            }
+            if let Some(error) = &editor.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Apply").clicked() {
+                    apply = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if !open || cancel {
+        app.breakpoint_editor = None;
+    } else if apply {
+        let Some(mut editor) = app.breakpoint_editor.take() else {
+            return;
+        };
+        match apply_changes(app, &editor) {
+            // Don't wait for the breakpoint event to show the change.
+            Ok(()) => app.breakpoint_index_stale = true,
+            Err(err) => {
+                editor.error = Some(err);
+                app.breakpoint_editor = Some(editor);
+            }
+        }
+    }
+}
+
+/// Sets the options of the breakpoint, after creating it for a new logpoint.
+fn apply_changes(app: &mut App, editor: &BreakpointEditor) -> Result<(), String> {
+    let options = editor.options()?;
+    let breakpoint = match &editor.breakpoint {
+        EditedBreakpoint::Existing(breakpoint) => breakpoint.clone(),
+        EditedBreakpoint::New(_) if editor.log_message.trim().is_empty() => {
+            return Err("the log message is empty".to_string());
+        }
+        EditedBreakpoint::New(spec) => app
+            .create_breakpoint(spec)
+            .map_err(|err| format!("Failed to create logpoint: {}", err))?,
+    };
+    debugger::set_breakpoint_options(&breakpoint, &options);
+    debugger::set_breakpoint_commands(&breakpoint, &editor.commands)
+        .map_err(|err| format!("Failed to set commands: {}", err))?;
+    for (location, _, enabled) in &editor.locations {
+        location.set_enabled(*enabled);
+    }
+    let id = breakpoint.id();
+    match editor.log_message.trim() {
+        "" => app.logpoints.remove(&id),
+        message => app.logpoints.insert(id, message.to_string()),
+    };
+    if editor.commands.script.trim().is_empty() {
+        app.breakpoint_commands.remove(&id);
+    } else {
+        app.breakpoint_commands.insert(id, editor.commands.clone());
+    }
+    Ok(())
+}
@@ -1,11 +1,104 @@
-use egui::{Color32, ScrollArea, Ui};
+use egui::collapsing_header::CollapsingState;
+use egui::{Align, Color32, Context, Layout, ScrollArea, TextEdit, Ui};
+use lldb::SBTarget;
 
-use crate::{
-    app::{widgets::IconButton, App, BreakpointsTab},
-    debugger,
-};
+use crate::app::components::{BreakpointEditor, FileBrowser, WatchpointEditor};
+use crate::app::saved_session::SavedBreakpoints;
+use crate::app::snapshot::BreakpointInfo;
+use crate::app::{widgets::IconButton, App, BreakpointsTab};
+use crate::debugger::{self, BreakpointSpec};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Function,
+    Regex,
+    SourceRegex,
+    Address,
+    CppThrow,
+    CppCatch,
+    RustPanic,
+}
+
+impl Kind {
+    const ALL: [Kind; 7] = [
+        Kind::Function,
+        Kind::Regex,
+        Kind::SourceRegex,
+        Kind::Address,
+        Kind::CppThrow,
+        Kind::CppCatch,
+        Kind::RustPanic,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Kind::Function => "function",
+            Kind::Regex => "symbol regex",
+            Kind::SourceRegex => "source regex",
+            Kind::Address => "address",
+            Kind::CppThrow => "C++ throw",
+            Kind::CppCatch => "C++ catch",
+            Kind::RustPanic => "Rust panic",
+        }
+    }
+
+    fn hint(self) -> Option<&'static str> {
+        match self {
+            Kind::Function => Some("main"),
+            Kind::Regex => Some("^foo::.*"),
+            Kind::SourceRegex => Some("// break here"),
+            Kind::Address => Some("0x1000"),
+            Kind::CppThrow | Kind::CppCatch | Kind::RustPanic => None,
+        }
+    }
+}
+
+/// State of the "add breakpoint" form of the breakpoints panel.
+pub struct BreakpointForm {
+    kind: Kind,
+    text: String,
+    error: Option<String>,
+}
+
+impl Default for BreakpointForm {
+    fn default() -> Self {
+        Self {
+            kind: Kind::Function,
+            text: String::new(),
+            error: None,
+        }
+    }
+}
+
+impl BreakpointForm {
+    fn spec(&self) -> Result<BreakpointSpec, String> {
+        let text = self.text.trim().to_string();
+        if text.is_empty() && self.kind.hint().is_some() {
+            return Err(format!("{} is empty", self.kind.label()));
+        }
+        Ok(match self.kind {
+            Kind::Function => BreakpointSpec::Function(text),
+            Kind::Regex => BreakpointSpec::Regex(text),
+            Kind::SourceRegex => BreakpointSpec::SourceRegex(text),
+            Kind::Address => BreakpointSpec::Address(
+                debugger::parse_address(&text)
+                    .ok_or_else(|| format!("invalid address: {}", text))?,
+            ),
+            Kind::CppThrow => BreakpointSpec::CppThrow,
+            Kind::CppCatch => BreakpointSpec::CppCatch,
+            Kind::RustPanic => BreakpointSpec::RustPanic,
+        })
+    }
+}
+
+/// The file the breakpoints are exported to or imported from.
+pub enum BreakpointsFile {
+    Export(FileBrowser),
+    Import(FileBrowser),
+}
 
 pub fn add(app: &mut App, ui: &mut Ui) {
+    breakpoints_file(app, ui.ctx());
     ui.horizontal(|ui| {
         ui.selectable_value(
             &mut app.breakpoints_tab,
@@ -17,42 +110,269 @@ pub fn add(app: &mut App, ui: &mut Ui) {
             BreakpointsTab::Watchpoints,
             "watchpoints",
         );
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            let dir = std::env::current_dir().unwrap_or_default();
+            if ui
+                .button("Import...")
+                .on_hover_text("add breakpoints from a JSON file")
+                .clicked()
+            {
+                let file_browser = FileBrowser::new("Import breakpoints", dir.clone());
+                app.breakpoints_file = Some(BreakpointsFile::Import(file_browser));
+            }
+            if ui
+                .button("Export...")
+                .on_hover_text("save the breakpoints to a JSON file")
+                .clicked()
+            {
+                let file_browser = FileBrowser::save("Export breakpoints", dir, "breakpoints.json");
+                app.breakpoints_file = Some(BreakpointsFile::Export(file_browser));
+            }
+        });
     });
+    match app.breakpoints_tab {
+        BreakpointsTab::Breakpoints => breakpoint_form(app, ui),
+        BreakpointsTab::Watchpoints => {
+            if ui.button("Add watchpoint...").clicked() {
+                app.watchpoint_editor = Some(WatchpointEditor::default());
+            }
+        }
+    }
+    let Some(index) = &app.breakpoint_index else {
+        return;
+    };
+    let mut edit = None;
+    let mut changed = false;
     ScrollArea::both()
         .id_source("breakpoints")
         .show(ui, |ui| match app.breakpoints_tab {
-            BreakpointsTab::Breakpoints => egui::Grid::new(ui.next_auto_id())
-                .num_columns(4)
-                .striped(true)
-                .show(ui, |ui| {
-                    for (id, file, line) in debugger::breakpoint_locations(&app.target).iter() {
-                        ui.label(format!("{}", id));
-                        ui.label(file);
-                        ui.label(format!("{}", line));
-                        if ui
-                            .add(IconButton::new_with_color("❌", "delete", Color32::RED))
-                            .clicked()
-                        {
-                            app.target.delete_breakpoint(*id)
+            BreakpointsTab::Breakpoints => {
+                for breakpoint in &index.breakpoints {
+                    breakpoint_row(ui, &app.target, breakpoint, &mut edit, &mut changed);
+                }
+            }
+            BreakpointsTab::Watchpoints => {
+                egui::Grid::new(ui.next_auto_id())
+                    .num_columns(8)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for watchpoint in &index.watchpoints {
+                            if ui
+                                .add(IconButton::new_with_color("❌", "delete", Color32::RED))
+                                .clicked()
+                            {
+                                app.target.delete_watchpoint(watchpoint.id)
+                            }
+                            ui.label(format!("{}", watchpoint.id));
+                            let watched = app.watched_values.get(&watchpoint.id);
+                            ui.strong(watched.map(|w| w.name.as_str()).unwrap_or_default());
+                            ui.label(watched.map(|w| w.type_name.as_str()).unwrap_or_default());
+                            ui.label(format!(
+                                "{:#x} ({} bytes)",
+                                watchpoint.address, watchpoint.size
+                            ));
+                            ui.label(watched.map(|w| w.kind.label()).unwrap_or_default());
+                            ui.label(format!("hits: {}", watchpoint.hit_count));
+                            if let Some(condition) = &watchpoint.condition {
+                                ui.label(format!("if {}", condition));
+                            } else {
+                                ui.label("");
+                            }
+                            ui.end_row()
                         }
-                        ui.end_row();
-                    }
-                }),
-            BreakpointsTab::Watchpoints => egui::Grid::new(ui.next_auto_id())
-                .num_columns(3)
+                    });
+            }
+        });
+
+    if let Some(breakpoint) = edit.and_then(|id| index.breakpoint(id)) {
+        app.breakpoint_editor = Some(BreakpointEditor::new(breakpoint));
+    }
+    // Don't wait for the breakpoint event to show the change.
+    if changed {
+        app.breakpoint_index_stale = true;
+    }
+}
+
+fn breakpoints_file(app: &mut App, ctx: &Context) {
+    let Some(breakpoints_file) = &mut app.breakpoints_file else {
+        return;
+    };
+    let mut open = true;
+    let (export, path) = match breakpoints_file {
+        BreakpointsFile::Export(file_browser) => (true, file_browser.show(ctx, &mut open)),
+        BreakpointsFile::Import(file_browser) => (false, file_browser.show(ctx, &mut open)),
+    };
+    if let Some(path) = path {
+        app.breakpoints_file = None;
+        if export {
+            let result = app.saved_breakpoints().export(&path);
+            if app
+                .notifications
+                .report("Failed to export breakpoints", result)
+                .is_some()
+            {
+                let message = format!("Exported breakpoints to {}", path.display());
+                app.notifications.info(message);
+            }
+        } else if let Some(saved) = app.notifications.report(
+            "Failed to import breakpoints",
+            SavedBreakpoints::import(&path),
+        ) {
+            saved.restore(app);
+            app.breakpoint_index_stale = true;
+        }
+    } else if !open {
+        app.breakpoints_file = None;
+    }
+}
+
+fn breakpoint_form(app: &mut App, ui: &mut Ui) {
+    let form = &mut app.breakpoint_form;
+    let mut spec = None;
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source("breakpoint_kind")
+            .selected_text(form.kind.label())
+            .show_ui(ui, |ui| {
+                for kind in Kind::ALL {
+                    ui.selectable_value(&mut form.kind, kind, kind.label());
+                }
+            });
+        let mut submit = false;
+        if let Some(hint) = form.kind.hint() {
+            let response = ui.add(TextEdit::singleline(&mut form.text).hint_text(hint));
+            submit = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        }
+        if ui.button("Add").clicked() || submit {
+            match form.spec() {
+                Ok(new_spec) => spec = Some(new_spec),
+                Err(err) => form.error = Some(err),
+            }
+        }
+        if ui
+            .button("Break on panic")
+            .on_hover_text("break in rust_panic")
+            .clicked()
+        {
+            spec = Some(BreakpointSpec::RustPanic);
+        }
+    });
+
+    if let Some(error) = &form.error {
+        ui.colored_label(ui.visuals().error_fg_color, error);
+    }
+
+    // The form is borrowed from the app, create the breakpoint after it is
+    // no longer used.
+    if let Some(spec) = spec {
+        match app.create_breakpoint(&spec) {
+            Ok(breakpoint) => {
+                tracing::debug!("breakpoint created: {:?}", breakpoint);
+                app.breakpoint_form.text.clear();
+                app.breakpoint_form.error = None;
+                // Don't wait for the breakpoint event to show the change.
+                app.breakpoint_index_stale = true;
+            }
+            Err(err) => app
+                .notifications
+                .error(format!("Failed to create breakpoint: {}", err)),
+        }
+    }
+}
+
+// The breakpoint as a collapsible row with its locations nested underneath.
+fn breakpoint_row(
+    ui: &mut Ui,
+    target: &SBTarget,
+    breakpoint: &BreakpointInfo,
+    edit: &mut Option<i32>,
+    changed: &mut bool,
+) {
+    let id = ui.make_persistent_id(("breakpoint", breakpoint.id));
+    CollapsingState::load_with_default_open(ui.ctx(), id, false)
+        .show_header(ui, |ui| {
+            if ui
+                .add(IconButton::new_with_color("❌", "delete", Color32::RED))
+                .clicked()
+            {
+                target.delete_breakpoint(breakpoint.id);
+            }
+            if ui.add(IconButton::new("✏", "edit")).clicked() {
+                *edit = Some(breakpoint.id);
+            }
+            let mut enabled = breakpoint.options.enabled;
+            if ui
+                .checkbox(&mut enabled, "")
+                .on_hover_text("enabled")
+                .changed()
+            {
+                breakpoint.breakpoint.set_enabled(enabled);
+                *changed = true;
+            }
+            ui.label(format!("{}", breakpoint.id));
+            if breakpoint.log_message.is_some() {
+                ui.strong("logpoint");
+            } else {
+                ui.strong(breakpoint.kind);
+            }
+            ui.label(&breakpoint.spec);
+            if breakpoint.is_pending() {
+                ui.colored_label(ui.visuals().warn_fg_color, "pending")
+                    .on_hover_text(
+                        "no location resolved yet, e.g. because the module isn't loaded",
+                    );
+            } else {
+                ui.label(format!("{} locations", breakpoint.locations.len()));
+            }
+            ui.label(format!("hits: {}", breakpoint.hit_count));
+            if let Some(condition) = &breakpoint.options.condition {
+                ui.label(format!("if {}", condition));
+            }
+            if let Some(commands) = &breakpoint.commands {
+                ui.label("+ commands").on_hover_text(&commands.script);
+            }
+            if let Some(log_message) = &breakpoint.log_message {
+                ui.label(format!("log \"{}\"", log_message));
+            }
+        })
+        .body(|ui| {
+            egui::Grid::new(id.with("locations"))
+                .num_columns(7)
                 .striped(true)
                 .show(ui, |ui| {
-                    for watchpoint in app.target.watchpoints() {
-                        ui.label(format!("{}", watchpoint.id()));
-                        ui.label(format!("{:#x}", watchpoint.watch_address()));
+                    for location in &breakpoint.locations {
+                        let mut enabled = location.enabled;
                         if ui
-                            .add(IconButton::new_with_color("❌", "delete", Color32::RED))
-                            .clicked()
+                            .checkbox(&mut enabled, "")
+                            .on_hover_text("enabled")
+                            .changed()
                         {
-                            app.target.delete_watchpoint(watchpoint.id())
+                            location.location.set_enabled(enabled);
+                            *changed = true;
+                        }
+                        ui.label(format!("{}.{}", breakpoint.id, location.id));
+                        ui.label(
+                            location
+                                .address
+                                .map(|address| format!("{:#x}", address))
+                                .unwrap_or_default(),
+                        );
+                        ui.label(location.function_name.as_deref().unwrap_or_default());
+                        if let Some((file, line)) = &location.line_entry {
+                            let name = file
+                                .file_name()
+                                .map(|name| name.to_string_lossy().to_string())
+                                .unwrap_or_default();
+                            ui.label(format!("{}:{}", name, line))
+                                .on_hover_text(file.display().to_string());
+                        } else {
+                            ui.label("");
                         }
-                        ui.end_row()
+                        ui.label(format!("hits: {}", location.hit_count));
+                        if !location.resolved {
+                            ui.colored_label(ui.visuals().warn_fg_color, "unresolved");
+                        }
+                        ui.end_row();
                     }
-                }),
+                });
         });
 }
@@ -1,12 +1,24 @@
-use egui::{Align, ScrollArea, Ui};
+use egui::text::{CCursor, CCursorRange};
+use egui::{Align, Id, Key, Modifiers, ScrollArea, TextEdit, Ui};
 
+use crate::app::console_history::HistorySearch;
+use crate::app::worker::Pending;
 use crate::app::{App, ConsoleTab};
+use crate::debugger;
 
 pub fn add(app: &mut App, ui: &mut Ui) {
+    if let Some(output) = app.console_command.poll() {
+        app.console_output.push_str(output);
+        app.console_command.reset();
+        // The command might have changed the process or the breakpoints.
+        app.invalidate_snapshot();
+    }
+
     ui.horizontal(|ui| {
         ui.selectable_value(&mut app.console_tab, ConsoleTab::Console, "console");
         ui.selectable_value(&mut app.console_tab, ConsoleTab::Stdout, "stdout");
         ui.selectable_value(&mut app.console_tab, ConsoleTab::Stderr, "stderr");
+        ui.selectable_value(&mut app.console_tab, ConsoleTab::Log, "log");
     });
     ScrollArea::both()
         .auto_shrink(false)
@@ -15,37 +27,132 @@ pub fn add(app: &mut App, ui: &mut Ui) {
         .show(ui, |ui| match app.console_tab {
             ConsoleTab::Console => {
                 ui.label(&app.console_output);
-                let response = ui.add(
-                    egui::TextEdit::singleline(&mut app.console_input)
-                        .hint_text("lldb command")
-                        .desired_width(f32::INFINITY),
-                );
-                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                    app.console_output
-                        .push_str(&format!("(lldb) {}\n", app.console_input));
-                    match app.target.debugger().execute_command(&app.console_input) {
-                        Ok(result) => app.console_output.push_str(result),
-                        Err(err) => app.console_output.push_str(&err),
-                    }
-                    app.console_output.push('\n');
-                    app.console_input.clear();
-                    response.scroll_to_me(Some(Align::Center));
-                    response.request_focus();
+                if app.console_command.is_running() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        if ui.button("Cancel").clicked() {
+                            debugger::interrupt(&app.target.process());
+                            app.console_command.reset();
+                            app.console_output.push_str("interrupted\n");
+                        }
+                    });
+                    return;
+                }
+                if app.console_history.search.is_some() {
+                    history_search(app, ui);
+                } else {
+                    command_input(app, ui);
                 }
             }
             ConsoleTab::Stdout => {
-                if let Some(output) = app.target.process().get_stdout_all() {
-                    app.process_stdout.push_str(&output);
-                }
                 ui.label(app.process_stdout.as_str());
             }
             ConsoleTab::Stderr => {
-                // TODO(ds): somehow stderr of the process ends up in stdout and this is always empty?
-                // https://github.com/llvm/llvm-project/issues/25350#issuecomment-980951241
-                if let Some(output) = app.target.process().get_stderr() {
-                    app.process_stderr.push_str(&output);
-                }
                 ui.label(app.process_stderr.as_str());
             }
+            ConsoleTab::Log => {
+                ui.label(app.log_output.as_str());
+            }
+        });
+}
+
+fn command_input(app: &mut App, ui: &mut Ui) {
+    let id = Id::new("console_input");
+    let history = &mut app.console_history;
+    let mut recalled = false;
+    if ui.memory(|memory| memory.has_focus(id)) {
+        // Take the keys before the text edit moves the cursor with them.
+        let (up, down, search) = ui.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(Modifiers::CTRL, Key::R),
+            )
         });
+        let command = if up {
+            history.previous(&app.console_input)
+        } else if down {
+            history.next()
+        } else {
+            None
+        };
+        if let Some(command) = command {
+            app.console_input = command.to_string();
+            recalled = true;
+        }
+        if search {
+            history.search = Some(HistorySearch::default());
+            ui.memory_mut(|memory| memory.request_focus(Id::new("console_history_search")));
+            return;
+        }
+    }
+
+    let mut output = TextEdit::singleline(&mut app.console_input)
+        .id(id)
+        .hint_text("lldb command, Ctrl-R to search the history")
+        .desired_width(f32::INFINITY)
+        .show(ui);
+    if recalled {
+        let end = CCursor::new(app.console_input.chars().count());
+        output
+            .state
+            .cursor
+            .set_char_range(Some(CCursorRange::one(end)));
+        output.state.store(ui.ctx(), id);
+    }
+    let response = output.response;
+    if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+        let command = std::mem::take(&mut app.console_input).trim().to_string();
+        response.request_focus();
+        // Empty commands aren't remembered, lldb repeats the previous one if
+        // it can, e.g. to keep stepping with `next`.
+        history.push(&command);
+        // Commands like `expression` can take a long time, run them on the
+        // worker.
+        let debugger = app.target.debugger();
+        app.console_command = Pending::Running(app.worker.run(move |_| {
+            let mut output = String::new();
+            debugger::execute_console_command(&debugger, &command, &mut output);
+            output
+        }));
+        response.scroll_to_me(Some(Align::Center));
+    }
+}
+
+// The reverse search of the history. Ctrl-R again finds an older command,
+// Enter puts the command found into the input and anything else cancels.
+fn history_search(app: &mut App, ui: &mut Ui) {
+    let older = ui.input_mut(|i| i.consume_key(Modifiers::CTRL, Key::R));
+    let history = &mut app.console_history;
+    let Some(search) = &mut history.search else {
+        return;
+    };
+    let response = ui
+        .horizontal(|ui| {
+            ui.label("reverse search:");
+            ui.add(
+                TextEdit::singleline(&mut search.query)
+                    .id(Id::new("console_history_search"))
+                    .desired_width(200.),
+            )
+        })
+        .inner;
+    if response.changed() {
+        history.find(false);
+    } else if older {
+        history.find(true);
+    }
+    match history.found() {
+        Some(command) => ui.monospace(command),
+        None => ui.weak("no match"),
+    };
+    if response.lost_focus() {
+        if ui.input(|i| i.key_pressed(Key::Enter)) {
+            if let Some(command) = history.found() {
+                app.console_input = command.to_string();
+            }
+        }
+        history.search = None;
+        ui.memory_mut(|memory| memory.request_focus(Id::new("console_input")));
+    }
 }
@@ -0,0 +1,81 @@
+use std::fs::read_dir;
+use std::path::PathBuf;
+
+use egui::{Context, ScrollArea};
+
+/// A minimal file chooser window.
+pub struct FileBrowser {
+    title: String,
+    dir: PathBuf,
+    /// The name of the file to save, `None` to pick an existing file.
+    file_name: Option<String>,
+}
+
+impl FileBrowser {
+    pub fn new(title: &str, dir: PathBuf) -> Self {
+        Self {
+            title: title.to_string(),
+            dir,
+            file_name: None,
+        }
+    }
+
+    /// A file chooser that also accepts a new file name to save to.
+    pub fn save(title: &str, dir: PathBuf, file_name: &str) -> Self {
+        Self {
+            file_name: Some(file_name.to_string()),
+            ..Self::new(title, dir)
+        }
+    }
+
+    /// Shows the window. Returns the picked file, `open` is set to false when
+    /// the window was closed.
+    pub fn show(&mut self, ctx: &Context, open: &mut bool) -> Option<PathBuf> {
+        let mut picked = None;
+        egui::Window::new(&self.title)
+            .collapsible(false)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.label(self.dir.display().to_string());
+                ui.separator();
+                ScrollArea::vertical().max_height(400.).show(ui, |ui| {
+                    if let Some(parent) = self.dir.parent() {
+                        if ui.link("..").clicked() {
+                            self.dir = parent.to_path_buf();
+                            return;
+                        }
+                    }
+                    let mut entries: Vec<PathBuf> = read_dir(&self.dir)
+                        .map(|entries| entries.filter_map(|e| Some(e.ok()?.path())).collect())
+                        .unwrap_or_default();
+                    entries.sort();
+                    for path in entries {
+                        let name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        if path.is_dir() {
+                            if ui.link(format!("{}/", name)).clicked() {
+                                self.dir = path;
+                            }
+                        } else if ui.link(&name).clicked() {
+                            match &mut self.file_name {
+                                Some(file_name) => *file_name = name,
+                                None => picked = Some(path),
+                            }
+                        }
+                    }
+                });
+                if let Some(file_name) = &mut self.file_name {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(file_name);
+                        if ui.button("Save").clicked() && !file_name.trim().is_empty() {
+                            picked = Some(self.dir.join(file_name.trim()));
+                        }
+                    });
+                }
+            });
+        picked
+    }
+}
@@ -3,6 +3,10 @@ use egui::{ScrollArea, Ui};
 use crate::app::App;
 
 pub fn add(app: &mut App, ui: &mut Ui) {
+    let Some(snapshot) = &app.snapshot else {
+        return;
+    };
+    let mut selected = None;
     ScrollArea::both()
         .id_source("frames")
         .auto_shrink(false)
@@ -11,43 +15,29 @@ pub fn add(app: &mut App, ui: &mut Ui) {
                 .num_columns(2)
                 .striped(true)
                 .show(ui, |ui| {
-                    if !app.target.process().is_stopped() {
-                        return;
-                    };
-
-                    let mut selected_frame_id = app
-                        .target
-                        .process()
-                        .selected_thread()
-                        .selected_frame()
-                        .frame_id();
-                    for frame in app.target.process().selected_thread().frames() {
-                        if let Some(line_entry) = frame.line_entry() {
-                            ui.label(format!(
-                                "{}:{}",
-                                line_entry.filespec().filename(),
-                                line_entry.line(),
-                            ));
-                        } else {
-                            ui.label("");
-                        }
-
+                    let mut selected_frame_id = snapshot.selected_frame_id;
+                    for frame in &snapshot.frames {
+                        ui.label(&frame.location);
                         if ui
                             .selectable_value(
                                 &mut selected_frame_id,
-                                frame.frame_id(),
-                                frame.display_function_name().unwrap(),
+                                frame.frame_id,
+                                &frame.function_name,
                             )
                             .clicked()
                         {
-                            app.target
-                                .process()
-                                .selected_thread()
-                                .set_selected_frame(frame.frame_id());
-                            app.source_file.clear(); // reset to make the source view scroll
+                            selected = Some(frame.frame_id);
                         }
                         ui.end_row();
                     }
                 });
         });
+
+    if let Some(frame_id) = selected {
+        app.target
+            .process()
+            .selected_thread()
+            .set_selected_frame(frame_id);
+        app.invalidate_snapshot();
+    }
 }
@@ -0,0 +1,151 @@
+use egui::{Context, TextEdit};
+
+use crate::app::App;
+use crate::debugger::{self, LaunchOptions};
+use crate::shell_words;
+
+/// Editable state of the launch dialog.
+pub struct LaunchDialog {
+    args: String,
+    working_dir: String,
+    env: String,
+    unset_env: String,
+    stdin: String,
+    stdout: String,
+    stderr: String,
+    disable_aslr: bool,
+    run_to_main: bool,
+    error: Option<String>,
+}
+
+impl LaunchDialog {
+    pub fn new(options: &LaunchOptions) -> Self {
+        Self {
+            args: shell_words::join(&options.args),
+            working_dir: options.working_dir.clone().unwrap_or_default(),
+            env: options.env.join("\n"),
+            unset_env: options.unset_env.join(" "),
+            stdin: options.stdin.clone().unwrap_or_default(),
+            stdout: options.stdout.clone().unwrap_or_default(),
+            stderr: options.stderr.clone().unwrap_or_default(),
+            disable_aslr: options.disable_aslr,
+            run_to_main: options.run_to_main,
+            error: None,
+        }
+    }
+
+    fn options(&self) -> Result<LaunchOptions, String> {
+        let non_empty = |s: &str| (!s.trim().is_empty()).then(|| s.trim().to_string());
+        Ok(LaunchOptions {
+            args: shell_words::split(&self.args).map_err(|err| format!("Args: {}", err))?,
+            working_dir: non_empty(&self.working_dir),
+            env: self
+                .env
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(debugger::parse_env_entry)
+                .collect::<Result<_, _>>()?,
+            unset_env: self
+                .unset_env
+                .split_whitespace()
+                .map(String::from)
+                .collect(),
+            stdin: non_empty(&self.stdin),
+            stdout: non_empty(&self.stdout),
+            stderr: non_empty(&self.stderr),
+            disable_aslr: self.disable_aslr,
+            run_to_main: self.run_to_main,
+        })
+    }
+}
+
+pub fn add(app: &mut App, ctx: &Context) {
+    let Some(dialog) = &mut app.launch_dialog else {
+        return;
+    };
+
+    let mut open = true;
+    let mut launch = false;
+    let mut cancel = false;
+    egui::Window::new("Launch")
+        .collapsible(false)
+        .resizable(false)
+        .open(&mut open)
+        .show(ctx, |ui| {
+            egui::Grid::new("launch_dialog")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Args:");
+                    ui.add(
+                        TextEdit::singleline(&mut dialog.args).hint_text("quoted like in a shell"),
+                    );
+                    ui.end_row();
+
+                    ui.label("Working dir:");
+                    ui.text_edit_singleline(&mut dialog.working_dir);
+                    ui.end_row();
+
+                    ui.label("Environment:");
+                    ui.add(
+                        TextEdit::multiline(&mut dialog.env)
+                            .hint_text("KEY=VALUE, one per line")
+                            .desired_rows(3),
+                    );
+                    ui.end_row();
+
+                    ui.label("Unset env:");
+                    ui.add(TextEdit::singleline(&mut dialog.unset_env).hint_text("KEY1 KEY2"));
+                    ui.end_row();
+
+                    ui.label("Stdin:");
+                    ui.text_edit_singleline(&mut dialog.stdin);
+                    ui.end_row();
+
+                    ui.label("Stdout:");
+                    ui.text_edit_singleline(&mut dialog.stdout);
+                    ui.end_row();
+
+                    ui.label("Stderr:");
+                    ui.text_edit_singleline(&mut dialog.stderr);
+                    ui.end_row();
+                });
+            ui.checkbox(&mut dialog.disable_aslr, "Disable ASLR");
+            ui.checkbox(&mut dialog.run_to_main, "Run to main");
+            if let Some(error) = &dialog.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Launch").clicked() {
+                    launch = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if !open || cancel {
+        app.launch_dialog = None;
+    } else if launch {
+        let options = match dialog.options() {
+            Ok(options) => options,
+            Err(err) => {
+                dialog.error = Some(err);
+                return;
+            }
+        };
+        match app.relaunch(Some(&options)) {
+            Ok(()) => {
+                app.launch_options = Some(options);
+                app.launch_dialog = None;
+                app.invalidate_snapshot();
+            }
+            Err(err) => {
+                if let Some(dialog) = &mut app.launch_dialog {
+                    dialog.error = Some(err.to_string());
+                }
+            }
+        }
+    }
+}
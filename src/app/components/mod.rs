@@ -1,21 +1,39 @@
+mod attach_progress;
 mod bottom_bar;
+mod breakpoint_editor;
 mod breakpoints;
 mod close_confirmation_dialog;
 mod console_tabs;
+mod file_browser;
 mod frames;
+mod launch_dialog;
+mod notifications;
 mod process_info;
 mod source_view;
+mod sources;
+mod start_screen;
+mod stop_banner;
 mod threads;
 mod top_bar;
 mod variables;
+mod watchpoint_editor;
 
+pub use attach_progress::add as attach_progress;
 pub use bottom_bar::add as bottom_bar;
-pub use breakpoints::add as breakpoints;
+pub use breakpoint_editor::{add as breakpoint_editor, BreakpointEditor};
+pub use breakpoints::{add as breakpoints, BreakpointForm, BreakpointsFile};
 pub use close_confirmation_dialog::add as close_confirmation;
 pub use console_tabs::add as console_tabs;
+pub use file_browser::FileBrowser;
 pub use frames::add as frames;
+pub use launch_dialog::{add as launch_dialog, LaunchDialog};
+pub use notifications::{add as notifications, history_button as notification_history_button};
 pub use process_info::add as process_info;
 pub use source_view::add as source_view;
+pub use sources::add as sources;
+pub use start_screen::{add as start_screen, StartScreen};
+pub use stop_banner::add as stop_banner;
 pub use threads::add as threads;
 pub use top_bar::add as top_bar;
 pub use variables::add as variables;
+pub use watchpoint_editor::{add as watchpoint_editor, WatchpointEditor};
@@ -0,0 +1,80 @@
+use egui::{Align2, Color32, Context, RichText, ScrollArea, Ui, Vec2};
+
+use crate::app::notifications::{Level, Notification, TOAST_DURATION};
+use crate::app::widgets::IconButton;
+use crate::app::App;
+
+/// Shows the recent notifications as toasts in the bottom right corner and
+/// the history window if it is open.
+pub fn add(app: &mut App, ctx: &Context) {
+    let notifications = &mut app.notifications;
+    let mut toasts = notifications.toasts().peekable();
+    if toasts.peek().is_some() {
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(Align2::RIGHT_BOTTOM, Vec2::new(-10., -40.))
+            .show(ctx, |ui| {
+                for toast in toasts {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(400.);
+                        ui.horizontal(|ui| {
+                            if ui.add(IconButton::new("❌", "dismiss")).clicked() {
+                                toast.dismissed = true;
+                            }
+                            message(ui, toast);
+                        });
+                    });
+                }
+            });
+        // Hide the toasts once they expired.
+        ctx.request_repaint_after(TOAST_DURATION);
+    }
+
+    let mut open = notifications.show_history;
+    egui::Window::new("Notifications")
+        .open(&mut open)
+        .default_width(500.)
+        .show(ctx, |ui| {
+            if ui.button("Clear").clicked() {
+                notifications.history.clear();
+            }
+            ui.separator();
+            ScrollArea::vertical()
+                .max_height(400.)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for notification in &notifications.history {
+                        ui.horizontal(|ui| {
+                            let age = notification.time.elapsed().as_secs();
+                            ui.label(RichText::new(format!("{}s ago", age)).weak());
+                            message(ui, notification);
+                        });
+                    }
+                });
+        });
+    notifications.show_history = open;
+}
+
+fn message(ui: &mut Ui, notification: &Notification) {
+    let color = match notification.level {
+        Level::Info => ui.visuals().text_color(),
+        Level::Error => ui.visuals().error_fg_color,
+    };
+    ui.add(egui::Label::new(RichText::new(&notification.message).color(color)).wrap(true));
+}
+
+/// The button to open the notification history, with the number of errors.
+pub fn history_button(app: &mut App, ui: &mut Ui) {
+    let errors = app
+        .notifications
+        .history
+        .iter()
+        .filter(|notification| notification.level == Level::Error)
+        .count();
+    let text = if errors > 0 {
+        RichText::new(format!("🔔 {}", errors)).color(Color32::RED)
+    } else {
+        RichText::new("🔔")
+    };
+    ui.toggle_value(&mut app.notifications.show_history, text.small())
+        .on_hover_text("notifications");
+}
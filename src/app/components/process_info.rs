@@ -13,6 +13,18 @@ pub fn add(app: &App, ui: &mut Ui) {
             }
             ui.end_row();
 
+            if let Some(remote) = &app.remote {
+                ui.label("Remote:");
+                ui.label(remote);
+                ui.end_row();
+            }
+
+            if let Some(core_file) = &app.core_file {
+                ui.label("Core:");
+                ui.label(core_file);
+                ui.end_row();
+            }
+
             ui.label("Args:");
             ui.label(
                 app.target
@@ -24,9 +36,20 @@ pub fn add(app: &App, ui: &mut Ui) {
             ui.end_row();
 
             ui.label("State:");
-            ui.label(format!("{:?}", app.target.process().state()));
+            ui.label(format!("{:?}", app.process_state));
             ui.end_row();
 
+            if let Some(process_exit) = &app.process_exit {
+                ui.label("Exit:");
+                let color = if process_exit.status == 0 && process_exit.description.is_none() {
+                    ui.visuals().text_color()
+                } else {
+                    ui.visuals().error_fg_color
+                };
+                ui.colored_label(color, process_exit.summary());
+                ui.end_row();
+            }
+
             ui.label("PID:");
             ui.label(format!("{}", app.target.process().process_id()));
             ui.end_row();
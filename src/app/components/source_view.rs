@@ -1,274 +1,436 @@
+use std::collections::HashMap;
 use std::fs::read_to_string;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
-use egui::{Align, Rect, RichText, ScrollArea, Ui};
-use egui_extras::syntax_highlighting::{highlight, CodeTheme};
-use lldb::SBCompileUnit;
+use egui::{Align, Layout, Rect, RichText, ScrollArea, Ui};
+use egui_extras::syntax_highlighting::CodeTheme;
+use lldb::LanguageType;
 
-use crate::app::widgets::{AnsiString, IconArrow, IconBreakpoint};
-use crate::app::App;
-use crate::debugger;
+use crate::app::components::{BreakpointEditor, FileBrowser};
+use crate::app::widgets::{
+    AnsiString, BreakpointState, IconArrow, IconBreakpoint, IconButton, IconLogpoint,
+};
+use crate::app::{syntax, App, LocateSource};
+use crate::debugger::{self, BreakpointSpec};
 
 pub fn add(app: &mut App, ui: &mut Ui) {
-    let frame = app.target.process().selected_thread().selected_frame();
-
-    if let Some(line_entry) = frame.line_entry() {
-        let scroll = line_entry.filespec().filename() != app.source_file
-            || line_entry.line() != app.source_line;
-        app.source_file = line_entry.filespec().filename().to_string();
-        app.source_line = line_entry.line();
-
-        let path: PathBuf = [
-            line_entry.filespec().directory(),
-            line_entry.filespec().filename(),
-        ]
-        .iter()
-        .collect();
-
-        let key = path.clone().into_os_string().into_string().unwrap();
-        ui.label(&key);
-        ui.separator();
+    tabs(app, ui);
+    match app.source_tab {
+        Some(i) => {
+            let path = app.open_sources[i].clone();
+            source_file(app, ui, &path, LanguageType::Unknown, None, false);
+        }
+        None => frame_source(app, ui),
+    }
+    locate_source(app, ui.ctx());
+}
 
-        if path.exists() {
-            let source = app
-                .source_cache
-                .entry(path.to_str().unwrap().to_string())
-                .or_insert(read_to_string(path).unwrap());
-            let theme = &CodeTheme::from_style(ui.style());
-            let language = detect_language(frame.compile_unit());
+// The tab of the selected frame followed by the tabs of the opened files.
+fn tabs(app: &mut App, ui: &mut Ui) {
+    if app.open_sources.is_empty() {
+        return;
+    }
+    let mut close = None;
+    ui.horizontal_wrapped(|ui| {
+        ui.selectable_value(&mut app.source_tab, None, "current frame");
+        for (i, path) in app.open_sources.iter().enumerate() {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            ui.selectable_value(&mut app.source_tab, Some(i), name)
+                .on_hover_text(path.display().to_string());
+            if ui.add(IconButton::new("❌", "close")).clicked() {
+                close = Some(i);
+            }
+            ui.separator();
+        }
+    });
+    ui.separator();
 
-            let row_height = ui.spacing().interact_size.y;
-            let total_rows = source.lines().count();
-            let target_line = line_entry.line() as usize;
+    if let Some(i) = close {
+        app.open_sources.remove(i);
+        app.source_tab = match app.source_tab {
+            Some(tab) if tab == i => None,
+            Some(tab) if tab > i => Some(tab - 1),
+            tab => tab,
+        };
+    }
+}
 
-            ScrollArea::both()
-                .auto_shrink(false)
-                .animated(false)
-                .show_rows(ui, row_height, total_rows, |ui, mut row_range| {
-                    let first = row_range.next().unwrap_or(0);
-                    let last = row_range.next_back().unwrap_or(first);
-                    let mut scroll_source_rect = Rect::NOTHING;
-                    egui::Grid::new("source")
-                        .num_columns(4)
-                        .min_col_width(5.0)
-                        .start_row(first)
-                        .with_row_color(move |i, style| {
-                            if i + 1 == target_line {
-                                Some(style.visuals.faint_bg_color)
-                            } else {
-                                None
-                            }
-                        })
-                        .show(ui, |ui| {
-                            let mut i = first;
-                            for line in source.lines().skip(i).take((last - first) + 1) {
-                                i += 1;
-                                let mut breakpoint = None;
-                                for (bp_id, bp_file, bp_line) in
-                                    debugger::breakpoint_locations(&app.target).iter()
-                                {
-                                    if line_entry.filespec().filename() == bp_file
-                                        && i == *bp_line as usize
-                                    {
-                                        breakpoint = Some(*bp_id);
-                                        break;
-                                    }
-                                }
-                                let hover_text = if breakpoint.is_some() {
-                                    "delete"
-                                } else {
-                                    "add breakpoint"
-                                };
-                                if ui
-                                    .add(IconBreakpoint::new(breakpoint.is_some()))
-                                    .on_hover_text(hover_text)
-                                    .clicked()
-                                {
-                                    if let Some(bp_id) = breakpoint {
-                                        app.target.delete_breakpoint(bp_id);
-                                    } else {
-                                        let bp = app.target.breakpoint_create_by_location(
-                                            line_entry.filespec().filename(),
-                                            i as u32,
-                                        );
-                                        tracing::debug!("breakpoint created: {:?}", bp);
-                                    }
-                                };
+// Shows the source of the selected frame, or its disassembly if there is none.
+fn frame_source(app: &mut App, ui: &mut Ui) {
+    let Some(snapshot) = &mut app.snapshot else {
+        return;
+    };
+    let frame = &mut snapshot.frame;
 
-                                if i == target_line {
-                                    ui.add(IconArrow::new(ui.style().visuals.warn_fg_color));
-                                } else {
-                                    ui.label(" ");
-                                }
+    if let Some(location) = &frame.source {
+        let path = location.path.clone();
+        let key = path.display().to_string();
+        let scroll = key != app.source_file || location.line != app.source_line;
+        app.source_file = key;
+        app.source_line = location.line;
 
-                                let mut line_number = RichText::new(format!("{}", i));
-                                if i == target_line {
-                                    line_number =
-                                        line_number.color(ui.style().visuals.warn_fg_color);
-                                }
-                                ui.label(line_number);
-                                let layout_job = highlight(ui.ctx(), theme, line, &language);
-                                let response =
-                                    ui.add(egui::Label::new(layout_job).selectable(true));
-                                // record location of first line to later calculate a scroll offset
-                                if scroll && i - 1 == first {
-                                    scroll_source_rect = response.rect;
-                                }
-                                ui.end_row();
-                            }
-                        });
-                    // scroll to the target line
-                    if scroll {
-                        let line_diff = target_line as i32 - (first + 1) as i32;
-                        let spacing_y = ui.spacing().item_spacing.y;
-                        let y_diff = line_diff as f32 * (row_height + spacing_y);
-                        scroll_source_rect.min.y += y_diff;
-                        scroll_source_rect.max.y += y_diff;
-                        ui.scroll_to_rect(scroll_source_rect, Some(Align::Center));
-                    };
-                });
-        } else {
-            tracing::info!("source file not found: {}", path.display());
-        }
+        let language = frame.language;
+        let line = location.line as usize;
+        source_file(app, ui, &path, language, Some(line), scroll);
     } else {
         // show disassembly
-        let function = frame.function();
-        if function.is_valid() {
-            ui.label(function.display_name());
+        if let Some(function_name) = &frame.function_name {
+            ui.label(function_name);
             ui.separator();
         }
-        let symbol = frame.symbol();
-        if symbol.is_valid() {
-            ui.label(symbol.display_name());
+        if let Some(symbol_name) = &frame.symbol_name {
+            ui.label(symbol_name);
             ui.separator();
         }
         ScrollArea::both()
             .auto_shrink(false)
             .animated(false)
             .show(ui, |ui| {
-                ui.add(AnsiString::new(frame.disassemble()));
+                if let Some(disassembly) = &frame.disassembly {
+                    ui.add(AnsiString::new(disassembly));
+                }
             });
     }
 }
 
-// https://github.com/trishume/syntect
-// Supported file types:
-// - Plain Text (.txt)
-// - ASP (.asa)
-// - HTML (ASP) (.asp)
-// - ActionScript (.as)
-// - AppleScript (.applescript, .script editor)
-// - Batch File (.bat, .cmd)
-// - NAnt Build File (.build)
-// - C# (.cs, .csx)
-// - C++ (.cpp, .cc, .cp, .cxx, .c++, .C, .h, .hh, .hpp, .hxx, .h++, .inl, .ipp)
-// - C (.c, .h)
-// - CSS (.css, .css.erb, .css.liquid)
-// - Clojure (.clj)
-// - D (.d, .di)
-// - Diff (.diff, .patch)
-// - Erlang (.erl, .hrl, .Emakefile, .emakefile)
-// - HTML (Erlang) (.yaws)
-// - Go (.go)
-// - Graphviz (DOT) (.dot, .DOT, .gv)
-// - Groovy (.groovy, .gvy, .gradle)
-// - HTML (.html, .htm, .shtml, .xhtml, .inc, .tmpl, .tpl)
-// - Haskell (.hs)
-// - Literate Haskell (.lhs)
-// - Java Server Page (JSP) (.jsp)
-// - Java (.java, .bsh)
-// - JavaDoc (.)
-// - Java Properties (.properties)
-// - JSON (.json, .sublime-settings, .sublime-menu, .sublime-keymap, .sublime-mousemap, .sublime-theme, .sublime-build, .sublime-project, .sublime-completions, .sublime-commands, .sublime-macro, .sublime-color-scheme)
-// - JavaScript (.js, .htc)
-// - Regular Expressions (Javascript) (.)
-// - BibTeX (.bib)
-// - LaTeX Log (.)
-// - LaTeX (.tex, .ltx)
-// - TeX (.sty, .cls)
-// - Lisp (.lisp, .cl, .clisp, .l, .mud, .el, .scm, .ss, .lsp, .fasl)
-// - Lua (.lua)
-// - Make Output (.)
-// - Makefile (.make, .GNUmakefile, .makefile, .Makefile, .OCamlMakefile, .mak, .mk)
-// - Markdown (.md, .mdown, .markdown, .markdn)
-// - MultiMarkdown (.)
-// - MATLAB (.matlab)
-// - OCaml (.ml, .mli)
-// - OCamllex (.mll)
-// - OCamlyacc (.mly)
-// - camlp4 (.)
-// - Objective-C++ (.mm, .M, .h)
-// - Objective-C (.m, .h)
-// - PHP Source (.)
-// - PHP (.php, .php3, .php4, .php5, .php7, .phps, .phpt, .phtml)
-// - Pascal (.pas, .p, .dpr)
-// - Perl (.pl, .pm, .pod, .t, .PL)
-// - Python (.py, .py3, .pyw, .pyi, .pyx, .pyx.in, .pxd, .pxd.in, .pxi, .pxi.in, .rpy, .cpy, .SConstruct, .Sconstruct, .sconstruct, .SConscript, .gyp, .gypi, .Snakefile, .wscript)
-// - Regular Expressions (Python) (.)
-// - R Console (.)
-// - R (.R, .r, .s, .S, .Rprofile)
-// - Rd (R Documentation) (.rd)
-// - HTML (Rails) (.rails, .rhtml, .erb, .html.erb)
-// - JavaScript (Rails) (.js.erb)
-// - Ruby Haml (.haml, .sass)
-// - Ruby on Rails (.rxml, .builder)
-// - SQL (Rails) (.erbsql, .sql.erb)
-// - Regular Expression (.re)
-// - reStructuredText (.rst, .rest)
-// - Ruby (.rb, .Appfile, .Appraisals, .Berksfile, .Brewfile, .capfile, .cgi, .Cheffile, .config.ru, .Deliverfile, .Fastfile, .fcgi, .Gemfile, .gemspec, .Guardfile, .irbrc, .jbuilder, .podspec, .prawn, .rabl, .rake, .Rakefile, .Rantfile, .rbx, .rjs, .ruby.rail, .Scanfile, .simplecov, .Snapfile, .thor, .Thorfile, .Vagrantfile)
-// - Cargo Build Results (.)
-// - Rust (.rs)
-// - SQL (.sql, .ddl, .dml)
-// - Scala (.scala, .sbt)
-// - Bourne Again Shell (bash) (.sh, .bash, .zsh, .fish, ..bash_aliases, ..bash_completions, ..bash_functions, ..bash_login, ..bash_logout, ..bash_profile, ..bash_variables, ..bashrc, ..profile, ..textmate_init)
-// - Shell-Unix-Generic (.)
-// - commands-builtin-shell-bash (.)
-// - HTML (Tcl) (.adp)
-// - Tcl (.tcl)
-// - Textile (.textile)
-// - XML (.xml, .xsd, .xslt, .tld, .dtml, .rss, .opml, .svg)
-// - YAML (.yaml, .yml, .sublime-syntax)
-fn detect_language(compile_unit: SBCompileUnit) -> String {
-    let str = match compile_unit.language() {
-        lldb::LanguageType::Unknown => todo!(),
-        lldb::LanguageType::C89 => "C",
-        lldb::LanguageType::C => "C",
-        lldb::LanguageType::Ada83 => todo!(),
-        lldb::LanguageType::C_plus_plus => "C++",
-        lldb::LanguageType::Cobol74 => todo!(),
-        lldb::LanguageType::Cobol85 => todo!(),
-        lldb::LanguageType::Fortran77 => todo!(),
-        lldb::LanguageType::Fortran90 => todo!(),
-        lldb::LanguageType::Pascal83 => todo!(),
-        lldb::LanguageType::Modula2 => todo!(),
-        lldb::LanguageType::Java => "Java",
-        lldb::LanguageType::C99 => "C",
-        lldb::LanguageType::Ada95 => todo!(),
-        lldb::LanguageType::Fortran95 => todo!(),
-        lldb::LanguageType::PLI => todo!(),
-        lldb::LanguageType::ObjC => "Objective-C",
-        lldb::LanguageType::ObjC_plus_plus => "Objective-C++",
-        lldb::LanguageType::UPC => todo!(),
-        lldb::LanguageType::D => "D",
-        lldb::LanguageType::Python => todo!(),
-        lldb::LanguageType::OpenCL => todo!(),
-        lldb::LanguageType::Go => "Go",
-        lldb::LanguageType::Modula3 => todo!(),
-        lldb::LanguageType::Haskell => todo!(),
-        lldb::LanguageType::C_plus_plus_03 => "C++",
-        lldb::LanguageType::C_plus_plus_11 => "C++",
-        lldb::LanguageType::OCaml => "OCaml",
-        lldb::LanguageType::Rust => "Rust",
-        lldb::LanguageType::C11 => "C",
-        lldb::LanguageType::Swift => todo!(),
-        lldb::LanguageType::Julia => todo!(),
-        lldb::LanguageType::Dylan => todo!(),
-        lldb::LanguageType::C_plus_plus_14 => "C++",
-        lldb::LanguageType::Fortran03 => todo!(),
-        lldb::LanguageType::Fortran08 => todo!(),
-        lldb::LanguageType::MipsAssembler => todo!(),
-        lldb::LanguageType::ExtRenderScript => todo!(),
-        lldb::LanguageType::NumLanguageTypes => todo!(),
+// Shows the source file with the breakpoint gutter. The `target_line` is
+// marked and scrolled to if `scroll` is set.
+fn source_file(
+    app: &mut App,
+    ui: &mut Ui,
+    path: &Path,
+    compile_unit_language: LanguageType,
+    target_line: Option<usize>,
+    scroll: bool,
+) {
+    let source_maps = debugger::executable_path(&app.target)
+        .and_then(|executable| app.source_maps.get(&executable));
+    // The path lldb knows the file by, breakpoints are matched against it.
+    let lldb_path = path;
+    let path = &remap_source_path(lldb_path, source_maps.map(Vec::as_slice));
+    let key = path.display().to_string();
+    let detected_language = syntax::detect_language(compile_unit_language, path);
+    ui.horizontal(|ui| {
+        ui.label(&key);
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            language_select(ui, &mut app.language_overrides, &key, &detected_language);
+        });
+    });
+    ui.separator();
+
+    if !path.exists() {
+        ui.colored_label(ui.visuals().warn_fg_color, "Source file not found.");
+        if ui.button("Locate file...").clicked() {
+            let dir = std::env::current_dir().unwrap_or_default();
+            app.locate_source = Some(LocateSource {
+                missing: path.clone(),
+                file_browser: FileBrowser::new("Locate source file", dir),
+            });
+        }
+        return;
+    }
+
+    let Some(index) = &app.breakpoint_index else {
+        return;
+    };
+    // The result is cached so an unreadable file isn't read, and the error
+    // reported, every frame.
+    let notifications = &mut app.notifications;
+    let source = match app.source_cache.entry(key.clone()).or_insert_with(|| {
+        read_to_string(path).map_err(|err| {
+            notifications.error(format!("Failed to read source file: {}", err));
+            err.to_string()
+        })
+    }) {
+        Ok(source) => source,
+        Err(err) => {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!("Failed to read source file: {}", err),
+            );
+            return;
+        }
+    };
+    let theme = &CodeTheme::from_style(ui.style());
+    let language = app
+        .language_overrides
+        .get(&key)
+        .unwrap_or(&detected_language);
+    let mut breakpoints_changed = false;
+    let mut edit_breakpoint = None;
+    let mut add_breakpoint = None;
+    let mut add_logpoint = None;
+
+    let row_height = ui.spacing().interact_size.y;
+    let total_rows = source.lines().count();
+    let target_line = target_line.unwrap_or(0);
+
+    ScrollArea::both()
+        .id_source(&key)
+        .auto_shrink(false)
+        .animated(false)
+        .show_rows(ui, row_height, total_rows, |ui, mut row_range| {
+            let first = row_range.next().unwrap_or(0);
+            let last = row_range.next_back().unwrap_or(first);
+            let mut scroll_source_rect = Rect::NOTHING;
+            egui::Grid::new("source")
+                .num_columns(4)
+                .min_col_width(5.0)
+                .start_row(first)
+                .with_row_color(move |i, style| {
+                    if i + 1 == target_line {
+                        Some(style.visuals.faint_bg_color)
+                    } else {
+                        None
+                    }
+                })
+                .show(ui, |ui| {
+                    let mut i = first;
+                    for line in source.lines().skip(i).take((last - first) + 1) {
+                        i += 1;
+                        let breakpoint = index.breakpoint_at(lldb_path, i as u32);
+                        let is_logpoint = breakpoint
+                            .and_then(|(bp_id, _)| index.breakpoint(bp_id))
+                            .is_some_and(|breakpoint| breakpoint.log_message.is_some());
+                        let response = match breakpoint {
+                            Some((_, enabled)) if is_logpoint => {
+                                ui.add(IconLogpoint::new(enabled)).on_hover_text("delete")
+                            }
+                            Some((_, true)) => ui
+                                .add(IconBreakpoint::new(BreakpointState::Enabled))
+                                .on_hover_text("delete"),
+                            Some((_, false)) => ui
+                                .add(IconBreakpoint::new(BreakpointState::Disabled))
+                                .on_hover_text("delete"),
+                            None => ui
+                                .add(IconBreakpoint::new(BreakpointState::None))
+                                .on_hover_text("add breakpoint"),
+                        };
+                        if response.clicked() {
+                            if let Some((bp_id, _)) = breakpoint {
+                                app.target.delete_breakpoint(bp_id);
+                                breakpoints_changed = true;
+                            } else {
+                                add_breakpoint = Some(i as u32);
+                            }
+                        };
+                        if let Some((bp_id, enabled)) = breakpoint {
+                            response.context_menu(|ui| {
+                                if ui.button("Edit...").clicked() {
+                                    edit_breakpoint = Some(bp_id);
+                                    ui.close_menu();
+                                }
+                                let toggle = if enabled { "Disable" } else { "Enable" };
+                                if ui.button(toggle).clicked() {
+                                    if let Some(breakpoint) = index.breakpoint(bp_id) {
+                                        breakpoint.breakpoint.set_enabled(!enabled);
+                                    }
+                                    breakpoints_changed = true;
+                                    ui.close_menu();
+                                }
+                            });
+                        } else {
+                            response.context_menu(|ui| {
+                                if ui.button("Add logpoint...").clicked() {
+                                    add_logpoint = Some(i as u32);
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+
+                        if i == target_line {
+                            ui.add(IconArrow::new(ui.style().visuals.warn_fg_color));
+                        } else {
+                            ui.label(" ");
+                        }
+
+                        let mut line_number = RichText::new(format!("{}", i));
+                        if i == target_line {
+                            line_number = line_number.color(ui.style().visuals.warn_fg_color);
+                        }
+                        ui.label(line_number);
+                        let layout_job = syntax::highlight(ui.ctx(), theme, line, language);
+                        let response = ui.add(egui::Label::new(layout_job).selectable(true));
+                        // record location of first line to later calculate a scroll offset
+                        if scroll && i - 1 == first {
+                            scroll_source_rect = response.rect;
+                        }
+                        ui.end_row();
+                    }
+                });
+            // scroll to the target line
+            if scroll {
+                let line_diff = target_line as i32 - (first + 1) as i32;
+                let spacing_y = ui.spacing().item_spacing.y;
+                let y_diff = line_diff as f32 * (row_height + spacing_y);
+                scroll_source_rect.min.y += y_diff;
+                scroll_source_rect.max.y += y_diff;
+                ui.scroll_to_rect(scroll_source_rect, Some(Align::Center));
+            };
+        });
+
+    if let Some(breakpoint) = edit_breakpoint.and_then(|id| index.breakpoint(id)) {
+        app.breakpoint_editor = Some(BreakpointEditor::new(breakpoint));
+    }
+    if let Some(line) = add_breakpoint {
+        let spec = BreakpointSpec::Line(lldb_path.to_path_buf(), line);
+        match app.create_breakpoint(&spec) {
+            Ok(bp) => tracing::debug!("breakpoint created: {:?}", bp),
+            Err(_) => app.notifications.error(format!(
+                "Failed to create breakpoint at {}:{}",
+                path.display(),
+                line
+            )),
+        }
+        breakpoints_changed = true;
+    }
+    // A logpoint is a breakpoint with a message, open the editor to enter it.
+    if let Some(line) = add_logpoint {
+        app.breakpoint_editor = Some(BreakpointEditor::new_logpoint(
+            lldb_path.to_path_buf(),
+            line,
+        ));
+    }
+    // Don't wait for the breakpoint event to show the change.
+    if breakpoints_changed {
+        app.breakpoint_index_stale = true;
+    }
+}
+
+// Lets the user override the detected language of the file.
+fn language_select(
+    ui: &mut Ui,
+    overrides: &mut HashMap<String, String>,
+    key: &str,
+    detected_language: &str,
+) {
+    let selected = overrides.get(key).cloned();
+    egui::ComboBox::from_id_source("source_language")
+        .selected_text(
+            selected
+                .clone()
+                .unwrap_or_else(|| format!("{} (detected)", detected_language)),
+        )
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(selected.is_none(), "detect").clicked() {
+                overrides.remove(key);
+            }
+            for language in syntax::LANGUAGES {
+                if ui
+                    .selectable_label(selected.as_deref() == Some(*language), *language)
+                    .clicked()
+                {
+                    overrides.insert(key.to_string(), language.to_string());
+                }
+            }
+        });
+}
+
+fn locate_source(app: &mut App, ctx: &egui::Context) {
+    let Some(locate_source) = &mut app.locate_source else {
+        return;
     };
-    str.to_string()
+    let mut open = true;
+    if let Some(found) = locate_source.file_browser.show(ctx, &mut open) {
+        let (old, new) = source_map(&locate_source.missing, &found);
+        app.locate_source = None;
+        app.add_source_map(old, new);
+    } else if !open {
+        app.locate_source = None;
+    }
+}
+
+// Strips the common trailing components of both paths, the remaining prefixes
+// map the missing file to the found one:
+// `/build/src/main.c` and `/home/me/src/main.c` map `/build` to `/home/me`.
+fn source_map(missing: &Path, found: &Path) -> (String, String) {
+    let mut old: Vec<Component> = missing.components().collect();
+    let mut new: Vec<Component> = found.components().collect();
+    while old.len() > 1 && new.len() > 1 && old.last() == new.last() {
+        old.pop();
+        new.pop();
+    }
+    let old: PathBuf = old.iter().collect();
+    let new: PathBuf = new.iter().collect();
+    (old.display().to_string(), new.display().to_string())
+}
+
+// Applies the source maps remembered for the executable, in case lldb didn't
+// remap the path of the line entry already. The longest matching prefix wins,
+// e.g. `/build/vendor` over `/build`.
+fn remap_source_path(path: &Path, source_maps: Option<&[(String, String)]>) -> PathBuf {
+    if path.exists() {
+        return path.to_path_buf();
+    }
+    source_maps
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|(old, new)| {
+            let rest = path.strip_prefix(old).ok()?;
+            Some((Path::new(old).components().count(), new, rest))
+        })
+        .max_by_key(|(prefix_len, _, _)| *prefix_len)
+        .map(|(_, new, rest)| Path::new(new).join(rest))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn maps(maps: &[(&str, &str)]) -> Vec<(String, String)> {
+        maps.iter()
+            .map(|(old, new)| (old.to_string(), new.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn remaps_with_the_longest_prefix() {
+        let maps = maps(&[("/build", "/src"), ("/build/vendor", "/deps")]);
+        assert_eq!(
+            remap_source_path(Path::new("/build/vendor/lib.c"), Some(&maps)),
+            Path::new("/deps/lib.c")
+        );
+        assert_eq!(
+            remap_source_path(Path::new("/build/main.c"), Some(&maps)),
+            Path::new("/src/main.c")
+        );
+    }
+
+    #[test]
+    fn keeps_paths_without_a_matching_prefix() {
+        let maps = maps(&[("/build", "/src")]);
+        // Only whole components match.
+        assert_eq!(
+            remap_source_path(Path::new("/builder/main.c"), Some(&maps)),
+            Path::new("/builder/main.c")
+        );
+        assert_eq!(
+            remap_source_path(Path::new("/other/main.c"), None),
+            Path::new("/other/main.c")
+        );
+    }
+
+    #[test]
+    fn remaps_prefixes_with_a_trailing_slash() {
+        let maps = maps(&[("/build/", "/src/")]);
+        assert_eq!(
+            remap_source_path(Path::new("/build/main.c"), Some(&maps)),
+            Path::new("/src/main.c")
+        );
+    }
+
+    #[test]
+    fn maps_the_differing_prefixes() {
+        assert_eq!(
+            source_map(
+                Path::new("/build/src/main.c"),
+                Path::new("/home/me/src/main.c")
+            ),
+            ("/build".to_string(), "/home/me".to_string())
+        );
+    }
 }
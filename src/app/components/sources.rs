@@ -0,0 +1,83 @@
+use egui::{CollapsingHeader, ScrollArea, TextEdit, Ui};
+
+use crate::app::snapshot::ModuleSources;
+use crate::app::widgets::show_pending;
+use crate::app::worker::Pending;
+use crate::app::App;
+
+pub fn add(app: &mut App, ui: &mut Ui) {
+    ui.add(
+        TextEdit::singleline(&mut app.sources_filter)
+            .hint_text("filter")
+            .desired_width(f32::INFINITY),
+    );
+    ui.separator();
+
+    if let Pending::NotStarted = app.sources {
+        let target = app.target.clone();
+        app.sources = Pending::Running(app.worker.run(move |_| ModuleSources::load(&target)));
+    }
+    let process = app.target.process();
+    let Some(modules) = show_pending(ui, &mut app.sources, &process) else {
+        return;
+    };
+
+    let filter = app.sources_filter.to_lowercase();
+    let mut open = None;
+    ScrollArea::both()
+        .id_source("sources")
+        .auto_shrink(false)
+        .show(ui, |ui| {
+            for module in modules.iter() {
+                let compile_units: Vec<_> = module
+                    .compile_units
+                    .iter()
+                    .filter(|compile_unit| {
+                        compile_unit
+                            .files
+                            .iter()
+                            .any(|file| file.display().to_string().to_lowercase().contains(&filter))
+                    })
+                    .collect();
+                if compile_units.is_empty() {
+                    continue;
+                }
+                CollapsingHeader::new(&module.name)
+                    .id_source(("sources_module", &module.name))
+                    .open((!filter.is_empty()).then_some(true))
+                    .show(ui, |ui| {
+                        for compile_unit in compile_units {
+                            let name = compile_unit
+                                .path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().to_string())
+                                .unwrap_or_default();
+                            CollapsingHeader::new(name)
+                                .id_source(("sources_compile_unit", &compile_unit.path))
+                                .open((!filter.is_empty()).then_some(true))
+                                .show(ui, |ui| {
+                                    for file in &compile_unit.files {
+                                        let path = file.display().to_string();
+                                        if !path.to_lowercase().contains(&filter) {
+                                            continue;
+                                        }
+                                        let name = file
+                                            .file_name()
+                                            .map(|name| name.to_string_lossy().to_string())
+                                            .unwrap_or_default();
+                                        if ui.link(name).on_hover_text(path).clicked() {
+                                            open = Some(file.clone());
+                                        }
+                                    }
+                                })
+                                .header_response
+                                .on_hover_text(compile_unit.path.display().to_string());
+                        }
+                    });
+            }
+        });
+
+    if let Some(path) = open {
+        app.open_source(path);
+    }
+}
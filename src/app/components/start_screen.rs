@@ -0,0 +1,232 @@
+use std::time::SystemTime;
+
+use anyhow::Result;
+use egui::{Context, ScrollArea, TextEdit, Ui};
+
+use crate::app::components::FileBrowser;
+use crate::app::{App, RecentSession};
+use crate::debugger::{self, LaunchOptions};
+use crate::processes::{self, Process};
+use crate::shell_words;
+
+/// State of the start screen that is shown when lldbui is started without a target.
+pub struct StartScreen {
+    executable: String,
+    args: String,
+    file_browser: Option<FileBrowser>,
+    filter: String,
+    processes: Vec<Process>,
+    error: Option<String>,
+}
+
+impl Default for StartScreen {
+    fn default() -> Self {
+        Self {
+            executable: String::new(),
+            args: String::new(),
+            file_browser: None,
+            filter: String::new(),
+            processes: processes::list(),
+            error: None,
+        }
+    }
+}
+
+enum Action {
+    Launch(RecentSession),
+    Attach(u64),
+}
+
+pub fn add(app: &mut App, ui: &mut Ui) {
+    let Some(screen) = &mut app.start_screen else {
+        return;
+    };
+    let mut action = None;
+
+    ui.heading("Launch");
+    egui::Grid::new("start_launch")
+        .num_columns(3)
+        .show(ui, |ui| {
+            ui.label("Executable:");
+            ui.add(TextEdit::singleline(&mut screen.executable).desired_width(400.));
+            if ui.button("Browse...").clicked() {
+                screen.file_browser = std::env::current_dir()
+                    .ok()
+                    .map(|dir| FileBrowser::new("Choose executable", dir));
+            }
+            ui.end_row();
+
+            ui.label("Args:");
+            ui.add(
+                TextEdit::singleline(&mut screen.args)
+                    .hint_text("quoted like in a shell")
+                    .desired_width(400.),
+            );
+            ui.end_row();
+        });
+    if ui
+        .add_enabled(!screen.executable.is_empty(), egui::Button::new("Launch"))
+        .clicked()
+    {
+        match shell_words::split(&screen.args) {
+            Ok(args) => {
+                action = Some(Action::Launch(RecentSession::Launch {
+                    executable: screen.executable.clone(),
+                    args,
+                }))
+            }
+            Err(err) => screen.error = Some(format!("Args: {}", err)),
+        }
+    }
+    if let Some(error) = &screen.error {
+        ui.colored_label(ui.visuals().error_fg_color, error);
+    }
+
+    if !app.recent_sessions.is_empty() {
+        ui.separator();
+        ui.heading("Recent");
+        for session in &app.recent_sessions {
+            let label = match session {
+                RecentSession::Launch { executable, args } => {
+                    format!("{} {}", executable, shell_words::join(args))
+                }
+                RecentSession::Core { executable, core } => {
+                    format!("{} --core {}", executable, core)
+                }
+            };
+            if ui.link(label).clicked() {
+                action = Some(Action::Launch(session.clone()));
+            }
+        }
+    }
+
+    ui.separator();
+    ui.heading("Attach");
+    ui.horizontal(|ui| {
+        ui.add(TextEdit::singleline(&mut screen.filter).hint_text("filter"));
+        if ui.button("Refresh").clicked() {
+            screen.processes = processes::list();
+        }
+    });
+    let filter = screen.filter.to_lowercase();
+    let now = SystemTime::now();
+    ScrollArea::both()
+        .id_source("processes")
+        .auto_shrink(false)
+        .show(ui, |ui| {
+            egui::Grid::new("processes")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    ui.strong("PID");
+                    ui.strong("User");
+                    ui.strong("Started");
+                    ui.strong("Command");
+                    ui.end_row();
+                    for process in screen.processes.iter().filter(|process| {
+                        process.pid.to_string().contains(&filter)
+                            || process.user.to_lowercase().contains(&filter)
+                            || process.command_line.to_lowercase().contains(&filter)
+                    }) {
+                        if ui.button("Attach").clicked() {
+                            action = Some(Action::Attach(process.pid));
+                        }
+                        ui.label(format!("{}", process.pid));
+                        ui.label(&process.user);
+                        ui.label(
+                            process
+                                .start_time
+                                .and_then(|start_time| now.duration_since(start_time).ok())
+                                .map(|age| format_age(age.as_secs()))
+                                .unwrap_or_default(),
+                        );
+                        ui.label(&process.command_line);
+                        ui.end_row();
+                    }
+                });
+        });
+
+    file_browser(screen, ui.ctx());
+
+    if let Some(action) = action {
+        if let Err(err) = start(app, action) {
+            if let Some(screen) = &mut app.start_screen {
+                screen.error = Some(err.to_string());
+            }
+        }
+    }
+}
+
+fn start(app: &mut App, action: Action) -> Result<()> {
+    let debugger = app.target.debugger();
+    match action {
+        Action::Launch(RecentSession::Launch { executable, args }) => {
+            let options = LaunchOptions {
+                args,
+                ..Default::default()
+            };
+            app.target = debugger::create_target(
+                &debugger,
+                &executable,
+                &options,
+                &app.pending_commands,
+                &mut app.console_output,
+            )?;
+            app.launch_options = Some(options);
+        }
+        Action::Launch(RecentSession::Core { executable, core }) => {
+            app.target = debugger::load_core(
+                &debugger,
+                &executable,
+                &core,
+                &app.pending_commands,
+                &mut app.console_output,
+            )?;
+            app.core_file = Some(core);
+        }
+        Action::Attach(pid) => {
+            app.target = debugger::attach_pid(
+                &debugger,
+                pid,
+                &app.pending_commands,
+                &mut app.console_output,
+            )?;
+        }
+    }
+    app.pending_commands.clear();
+    app.process_state = app.target.process().state();
+    app.start_screen = None;
+    app.add_recent_session();
+    app.apply_source_maps();
+    app.restore_session();
+    if app.launch_options.is_some() {
+        app.launch();
+    }
+    app.invalidate_snapshot();
+    app.sources.reset();
+    Ok(())
+}
+
+fn file_browser(screen: &mut StartScreen, ctx: &Context) {
+    let Some(file_browser) = &mut screen.file_browser else {
+        return;
+    };
+    let mut open = true;
+    if let Some(path) = file_browser.show(ctx, &mut open) {
+        screen.executable = path.display().to_string();
+        open = false;
+    }
+    if !open {
+        screen.file_browser = None;
+    }
+}
+
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => format!("{}s ago", seconds),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        3600..=86399 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
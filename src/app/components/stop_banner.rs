@@ -0,0 +1,236 @@
+use egui::{RichText, Ui};
+
+use crate::app::snapshot::file_path;
+use crate::app::App;
+use crate::debugger::{self, StopCause, StopInfo};
+
+/// Explains why the process stopped or exited, above the source view. On a
+/// crash it offers to jump to the source and to copy a summary for a bug
+/// report.
+pub fn add(app: &mut App, ui: &mut Ui) {
+    if app.process_exit.is_some() {
+        process_exit(app, ui);
+        return;
+    }
+    let Some(stop) = app
+        .snapshot
+        .as_ref()
+        .and_then(|snapshot| snapshot.stop.as_ref())
+    else {
+        return;
+    };
+    let Some(title) = title(app, stop) else {
+        return;
+    };
+
+    let color = if stop.crashed {
+        ui.visuals().error_fg_color
+    } else {
+        ui.visuals().warn_fg_color
+    };
+    ui.label(RichText::new(title).color(color).strong());
+    if !stop.description.is_empty() {
+        ui.label(&stop.description);
+    }
+
+    egui::Grid::new("stop_banner")
+        .num_columns(2)
+        .show(ui, |ui| match &stop.cause {
+            StopCause::Signal {
+                code,
+                fault_address,
+                ..
+            } => {
+                if let Some(code) = code {
+                    ui.label("code:");
+                    ui.monospace(code);
+                    ui.end_row();
+                }
+                if let Some((address, region)) = fault_address {
+                    ui.label("fault address:");
+                    ui.monospace(format!("{:#x}", address));
+                    ui.end_row();
+
+                    ui.label("memory region:");
+                    ui.monospace(region);
+                    ui.end_row();
+                }
+            }
+            StopCause::Watchpoint(id) => {
+                let Some(change) = app.watchpoint_change.as_ref().filter(|c| c.id == *id) else {
+                    return;
+                };
+                ui.label("old value:");
+                ui.monospace(
+                    change
+                        .old_value
+                        .as_deref()
+                        .map(format_memory)
+                        .unwrap_or_else(|| "unknown".to_string()),
+                );
+                ui.end_row();
+
+                ui.label("new value:");
+                ui.monospace(format_memory(&change.new_value));
+                ui.end_row();
+            }
+            _ => {}
+        });
+
+    let mut jump_to_source = false;
+    if stop.crashed {
+        ui.horizontal(|ui| {
+            if ui
+                .button("Jump to source")
+                .on_hover_text("select the first frame of the thread with source")
+                .clicked()
+            {
+                jump_to_source = true;
+            }
+            if ui
+                .button("Copy triage summary")
+                .on_hover_text("copy the stop reason and backtrace to the clipboard")
+                .clicked()
+            {
+                let summary = triage_summary(app, stop);
+                ui.output_mut(|output| output.copied_text = summary);
+            }
+        });
+    }
+    ui.separator();
+
+    if jump_to_source {
+        let thread = stop.thread.clone();
+        if let Some(frame) = debugger::first_frame_with_source(&thread) {
+            app.target.process().set_selected_thread(&thread);
+            thread.set_selected_frame(frame.frame_id());
+            app.invalidate_snapshot();
+        }
+    }
+}
+
+// Shows the exit code and offers to launch the process again with the same
+// launch info.
+fn process_exit(app: &mut App, ui: &mut Ui) {
+    let Some(process_exit) = &app.process_exit else {
+        return;
+    };
+    let mut run_again = false;
+    ui.horizontal(|ui| {
+        ui.label(RichText::new(format!("Process {}", process_exit.summary())).strong());
+        if app.launch_options.is_some() && ui.button("Run again").clicked() {
+            run_again = true;
+        }
+    });
+    ui.separator();
+
+    if run_again {
+        let result = app.relaunch(None);
+        if app
+            .notifications
+            .report("Failed to run process again", result)
+            .is_some()
+        {
+            app.process_exit = None;
+            app.invalidate_snapshot();
+        }
+    }
+}
+
+// A readable summary of why the process stopped, `None` if it isn't worth a
+// banner, e.g. after a step.
+fn title(app: &App, stop: &StopInfo) -> Option<String> {
+    let thread_id = stop.thread.thread_id();
+    let title = match &stop.cause {
+        // Running to `main` after the launch.
+        StopCause::Breakpoint(id, _) if Some(*id) == app.run_to_main_breakpoint => return None,
+        StopCause::Breakpoint(id, location_id) => {
+            let mut title = format!("Stopped at breakpoint {}.{}", id, location_id);
+            let breakpoint = app
+                .breakpoint_index
+                .as_ref()
+                .and_then(|index| index.breakpoint(*id));
+            if let Some(breakpoint) = breakpoint {
+                title.push_str(&format!(": {} {}", breakpoint.kind, breakpoint.spec));
+            }
+            title
+        }
+        StopCause::Watchpoint(id) => {
+            let mut title = format!("Stopped at watchpoint {}", id);
+            if let Some(watched) = app.watched_values.get(id) {
+                title.push_str(&format!(": {}", watched.name));
+                if !watched.type_name.is_empty() {
+                    title.push_str(&format!(" ({})", watched.type_name));
+                }
+            }
+            title
+        }
+        StopCause::Signal { number, name, .. } => format!(
+            "Thread {} received {} ({})",
+            thread_id,
+            name.as_deref().unwrap_or("signal"),
+            number
+        ),
+        StopCause::Exception => format!("Thread {} stopped with an exception", thread_id),
+        StopCause::Other => return None,
+    };
+    Some(title)
+}
+
+// Text to paste into a bug report: the executable, why it stopped and the
+// backtrace of the thread.
+fn triage_summary(app: &App, stop: &StopInfo) -> String {
+    let mut summary = String::new();
+    if let Some(executable) = debugger::executable_path(&app.target) {
+        summary.push_str(&format!("executable: {}\n", executable));
+    }
+    if let Some(title) = title(app, stop) {
+        summary.push_str(&format!("{}\n", title));
+    }
+    if !stop.description.is_empty() {
+        summary.push_str(&format!("{}\n", stop.description));
+    }
+    if let StopCause::Signal {
+        fault_address: Some((address, region)),
+        ..
+    } = &stop.cause
+    {
+        summary.push_str(&format!("fault address: {:#x} in {}\n", address, region));
+    }
+    summary.push_str(&format!(
+        "\nthread {} {}\n",
+        stop.thread.thread_id(),
+        stop.thread.name().unwrap_or_default()
+    ));
+    for (i, frame) in stop.thread.frames().enumerate() {
+        summary.push_str(&format!(
+            "  #{} {:#x} {}",
+            i,
+            frame.pc(),
+            frame.display_function_name().unwrap_or("???")
+        ));
+        if let Some(line_entry) = frame.line_entry() {
+            let path = file_path(&line_entry.filespec());
+            summary.push_str(&format!(" at {}:{}", path.display(), line_entry.line()));
+        }
+        summary.push('\n');
+    }
+    summary
+}
+
+// Values up to 8 bytes are shown as an integer, assuming a little endian
+// target, larger ones as hex bytes.
+fn format_memory(bytes: &[u8]) -> String {
+    if bytes.len() <= 8 {
+        let mut buffer = [0; 8];
+        buffer[..bytes.len()].copy_from_slice(bytes);
+        let value = u64::from_le_bytes(buffer);
+        format!("{} ({:#x})", value, value)
+    } else {
+        bytes
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
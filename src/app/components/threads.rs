@@ -1,9 +1,12 @@
 use egui::{ScrollArea, Ui};
-use lldb::SBThread;
 
 use crate::app::App;
 
 pub fn add(app: &mut App, ui: &mut Ui) {
+    let Some(snapshot) = &app.snapshot else {
+        return;
+    };
+    let mut selected = None;
     ScrollArea::both()
         .id_source("threads")
         .auto_shrink(false)
@@ -12,40 +15,25 @@ pub fn add(app: &mut App, ui: &mut Ui) {
                 .num_columns(1)
                 .striped(true)
                 .show(ui, |ui| {
-                    if !app.target.process().is_stopped() {
-                        return;
-                    }
-
-                    let mut selected_thread_id = app.target.process().selected_thread().thread_id();
-                    for thread in app.target.process().threads() {
+                    let mut selected_thread_id = snapshot.selected_thread_id;
+                    for thread in &snapshot.threads {
                         if ui
                             .selectable_value(
                                 &mut selected_thread_id,
-                                thread.thread_id(),
-                                thread_label(&thread),
+                                thread.thread.thread_id(),
+                                &thread.label,
                             )
                             .clicked()
                         {
-                            app.target.process().set_selected_thread(&thread);
-                            app.source_file.clear(); // reset to make the source view scroll
+                            selected = Some(thread.thread.clone());
                         }
                         ui.end_row();
                     }
                 });
         });
-}
 
-fn thread_label(thread: &SBThread) -> String {
-    let mut label = format!(
-        "{} {}",
-        thread.thread_id(),
-        thread.name().unwrap_or_default()
-    );
-    if let Some(queue) = thread.queue() {
-        label.push_str(&format!(" queue={}", queue.name()));
-    }
-    if thread.is_stopped() {
-        label.push_str(&format!(" stop_reason={:?}", thread.stop_reason()));
+    if let Some(thread) = selected {
+        app.target.process().set_selected_thread(&thread);
+        app.invalidate_snapshot();
     }
-    label
 }
@@ -2,35 +2,40 @@ use egui::{Align, Color32, Layout, Ui};
 use lldb::RunMode;
 
 use crate::{
-    app::{widgets::IconButton, App},
+    app::{components::LaunchDialog, widgets::IconButton, App},
     debugger,
 };
 
 pub fn add(app: &mut App, ui: &mut Ui) {
     let process = app.target.process();
+    let state = app.process_state;
+    // A core file is a snapshot of a dead process, there is nothing to run or step.
+    let can_control = app.core_file.is_none();
 
     ui.horizontal(|ui| {
         if ui
             .add_enabled(
-                debugger::process_can_stop(&process),
+                can_control && debugger::state_can_stop(state),
                 IconButton::new_with_color("⏸", "Stop", Color32::RED),
             )
             .clicked()
         {
-            process.stop().unwrap();
+            app.notifications
+                .report("Failed to stop process", process.stop());
         }
         if ui
             .add_enabled(
-                debugger::process_can_continue(&process),
+                can_control && debugger::state_can_continue(state),
                 IconButton::new_with_color("⏵", "Continue", Color32::GREEN),
             )
             .clicked()
         {
-            process.continue_execution().unwrap();
+            app.notifications
+                .report("Failed to continue", process.continue_execution());
         }
         if ui
             .add_enabled(
-                debugger::process_can_continue(&process),
+                can_control && debugger::state_can_continue(state),
                 IconButton::new("⬇", "Step Into"),
             )
             .clicked()
@@ -41,24 +46,69 @@ pub fn add(app: &mut App, ui: &mut Ui) {
         }
         if ui
             .add_enabled(
-                debugger::process_can_continue(&process),
+                can_control && debugger::state_can_continue(state),
                 IconButton::new("⬈", "Step Over"),
             )
             .clicked()
         {
-            process
-                .selected_thread()
-                .step_over(RunMode::OnlyDuringStepping)
-                .unwrap();
+            app.notifications.report(
+                "Failed to step over",
+                process
+                    .selected_thread()
+                    .step_over(RunMode::OnlyDuringStepping),
+            );
         }
         if ui
             .add_enabled(
-                debugger::process_frame_has_parent(&process),
+                can_control
+                    && app
+                        .snapshot
+                        .as_ref()
+                        .is_some_and(|snapshot| snapshot.frame_has_parent()),
                 IconButton::new("⬆", "Step Out"),
             )
             .clicked()
         {
-            process.selected_thread().step_out().unwrap();
+            app.notifications
+                .report("Failed to step out", process.selected_thread().step_out());
+        }
+
+        ui.separator();
+        if ui
+            .add_enabled(
+                app.launch_options.is_some(),
+                IconButton::new("🔄", "Restart"),
+            )
+            .clicked()
+        {
+            let result = app.relaunch(None);
+            if app
+                .notifications
+                .report("Failed to restart process", result)
+                .is_some()
+            {
+                app.invalidate_snapshot();
+            }
+        }
+        if ui
+            .add_enabled(
+                can_control && debugger::state_is_alive(state),
+                IconButton::new_with_color("⏹", "Kill", Color32::RED),
+            )
+            .clicked()
+        {
+            app.notifications
+                .report("Failed to kill process", debugger::kill(&process));
+        }
+        if ui
+            .add_enabled(
+                can_control && debugger::state_is_alive(state),
+                IconButton::new("⏏", "Detach"),
+            )
+            .clicked()
+        {
+            app.notifications
+                .report("Failed to detach from process", debugger::detach(&process));
         }
 
         ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
@@ -66,6 +116,12 @@ pub fn add(app: &mut App, ui: &mut Ui) {
                 app.show_confirmation_dialog = true;
             }
             ui.separator();
+            ui.toggle_value(&mut app.show_sources, "Sources");
+            if let Some(launch_options) = &app.launch_options {
+                if ui.button("Launch...").clicked() {
+                    app.launch_dialog = Some(LaunchDialog::new(launch_options));
+                }
+            }
         })
     });
 }
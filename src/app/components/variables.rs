@@ -1,35 +1,111 @@
-use egui::{ScrollArea, Ui};
+use egui::{Color32, ScrollArea, TextEdit, Ui};
 
-use crate::app::{widgets::VariableList, App, VariablesTab};
+use crate::app::components::WatchpointEditor;
+use crate::app::snapshot::WatchExpression;
+use crate::app::widgets::{show_pending, IconButton, VariableList};
+use crate::app::{App, VariablesTab};
 
 pub fn add(app: &mut App, ui: &mut Ui) {
-    let frame = app.target.process().selected_thread().selected_frame();
     ui.horizontal(|ui| {
         ui.selectable_value(&mut app.variables_tab, VariablesTab::Locals, "locals");
         ui.selectable_value(&mut app.variables_tab, VariablesTab::Statics, "statics");
         ui.selectable_value(&mut app.variables_tab, VariablesTab::Arguments, "arguments");
         ui.selectable_value(&mut app.variables_tab, VariablesTab::Registers, "registers");
+        ui.selectable_value(&mut app.variables_tab, VariablesTab::Watch, "watch");
     });
+    if app.variables_tab == VariablesTab::Watch {
+        ScrollArea::both()
+            .id_source("variables")
+            .auto_shrink(false)
+            .show(ui, |ui| watch_expressions(app, ui));
+        return;
+    }
+    let (Some(snapshot), Some(index)) = (&mut app.snapshot, &app.breakpoint_index) else {
+        return;
+    };
+    let mut watch = None;
     ScrollArea::both()
         .id_source("variables")
         .auto_shrink(false)
         .show(ui, |ui| {
-            if !app.target.process().is_stopped() {
-                return;
-            }
-            match app.variables_tab {
-                VariablesTab::Locals => {
-                    ui.add(VariableList::new(frame.locals().iter(), &app.target));
-                }
-                VariablesTab::Statics => {
-                    ui.add(VariableList::new(frame.statics().iter(), &app.target));
-                }
-                VariablesTab::Arguments => {
-                    ui.add(VariableList::new(frame.arguments().iter(), &app.target));
-                }
-                VariablesTab::Registers => {
-                    ui.add(VariableList::new(frame.registers().iter(), &app.target));
-                }
+            let variables = match app.variables_tab {
+                VariablesTab::Locals => &mut snapshot.locals,
+                VariablesTab::Statics => &mut snapshot.statics,
+                VariablesTab::Arguments => &mut snapshot.arguments,
+                VariablesTab::Registers => &mut snapshot.registers,
+                VariablesTab::Watch => return,
+            };
+            ui.add(VariableList::new(
+                variables,
+                &app.target,
+                &index.watched_addresses,
+                &app.worker,
+                &mut watch,
+            ));
+        });
+    if let Some(request) = watch {
+        app.watchpoint_editor = Some(WatchpointEditor::new(&request));
+    }
+}
+
+fn watch_expressions(app: &mut App, ui: &mut Ui) {
+    let process = app.target.process();
+    let stopped = app.snapshot.is_some();
+    let mut remove = None;
+    let mut watch = None;
+    for (i, watch_expression) in app.watch_expressions.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            if ui
+                .add(IconButton::new_with_color("❌", "remove", Color32::RED))
+                .clicked()
+            {
+                remove = Some(i);
             }
+            ui.label(&watch_expression.expression);
         });
+        // Expressions can only be evaluated in a stopped process.
+        if !stopped {
+            continue;
+        }
+        let Some(index) = &app.breakpoint_index else {
+            continue;
+        };
+        watch_expression.evaluate(&process, &app.worker);
+        match show_pending(ui, &mut watch_expression.value, &process) {
+            Some(Ok(variable)) => {
+                ui.add(VariableList::new(
+                    std::slice::from_mut(variable),
+                    &app.target,
+                    &index.watched_addresses,
+                    &app.worker,
+                    &mut watch,
+                ));
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, err.as_str());
+            }
+            None => {}
+        }
+    }
+    if let Some(i) = remove {
+        app.watch_expressions.remove(i).value.reset();
+    }
+    if let Some(request) = watch {
+        app.watchpoint_editor = Some(WatchpointEditor::new(&request));
+    }
+
+    let response = ui.add(
+        TextEdit::singleline(&mut app.watch_input)
+            .hint_text("expression")
+            .desired_width(f32::INFINITY),
+    );
+    if response.lost_focus()
+        && ui.input(|i| i.key_pressed(egui::Key::Enter))
+        && !app.watch_input.trim().is_empty()
+    {
+        let expression = app.watch_input.trim().to_string();
+        app.watch_expressions.push(WatchExpression::new(expression));
+        app.watch_input.clear();
+        response.request_focus();
+    }
 }
@@ -0,0 +1,178 @@
+use egui::{Context, TextEdit};
+
+use crate::app::snapshot::WatchedValue;
+use crate::app::widgets::WatchRequest;
+use crate::app::worker::Pending;
+use crate::app::App;
+use crate::debugger::{self, WatchKind, WatchpointOptions};
+
+/// Editable state of the watchpoint editor, it adds a new watchpoint.
+#[derive(Default)]
+pub struct WatchpointEditor {
+    /// An expression like `foo.bar` or an address.
+    target: String,
+    /// The name of the watched variable, shown instead of the address.
+    name: Option<String>,
+    type_name: String,
+    size: String,
+    kind: WatchKind,
+    condition: String,
+    ignore_count: String,
+    /// The address, size and type of the expression evaluated on the worker.
+    resolved: Pending<anyhow::Result<(u64, usize, String)>>,
+    error: Option<String>,
+}
+
+impl WatchpointEditor {
+    pub fn new(request: &WatchRequest) -> Self {
+        Self {
+            target: format!("{:#x}", request.address),
+            name: Some(request.name.clone()),
+            type_name: request.type_name.clone(),
+            size: request.size.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn options(&self, size: usize) -> Result<WatchpointOptions, String> {
+        let size = match self.size.trim() {
+            "" => size,
+            size => size
+                .parse()
+                .map_err(|_| format!("invalid size: {}", size))?,
+        };
+        if size == 0 {
+            return Err("size is missing".to_string());
+        }
+        let ignore_count = match self.ignore_count.trim() {
+            "" => 0,
+            count => count
+                .parse()
+                .map_err(|_| format!("invalid ignore count: {}", count))?,
+        };
+        let condition = self.condition.trim();
+        Ok(WatchpointOptions {
+            size,
+            kind: self.kind,
+            condition: (!condition.is_empty()).then(|| condition.to_string()),
+            ignore_count,
+        })
+    }
+}
+
+pub fn add(app: &mut App, ctx: &Context) {
+    let Some(editor) = &mut app.watchpoint_editor else {
+        return;
+    };
+
+    let mut open = true;
+    let mut add = false;
+    let mut cancel = false;
+    egui::Window::new("Add watchpoint")
+        .collapsible(false)
+        .resizable(false)
+        .open(&mut open)
+        .show(ctx, |ui| {
+            egui::Grid::new("watchpoint_editor")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Watch:");
+                    ui.add(
+                        TextEdit::singleline(&mut editor.target).hint_text("expression or 0x1000"),
+                    );
+                    ui.end_row();
+
+                    ui.label("Size:");
+                    ui.add(TextEdit::singleline(&mut editor.size).hint_text("size of the value"));
+                    ui.end_row();
+
+                    ui.label("Trigger on:");
+                    ui.horizontal(|ui| {
+                        for kind in WatchKind::ALL {
+                            ui.selectable_value(&mut editor.kind, kind, kind.label());
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Condition:");
+                    ui.add(TextEdit::singleline(&mut editor.condition).hint_text("x > 42"));
+                    ui.end_row();
+
+                    ui.label("Ignore count:");
+                    ui.text_edit_singleline(&mut editor.ignore_count);
+                    ui.end_row();
+                });
+            if let Some(error) = &editor.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if editor.resolved.is_running() {
+                    ui.spinner();
+                } else if ui.button("Add").clicked() {
+                    add = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if !open || cancel {
+        editor.resolved.reset();
+        app.watchpoint_editor = None;
+        return;
+    }
+    if add {
+        let target = editor.target.trim().to_string();
+        if let Some(address) = debugger::parse_address(&target) {
+            editor.resolved = Pending::Done(Ok((address, 0, editor.type_name.clone())));
+        } else if app.snapshot.is_some() {
+            // Expressions are evaluated in the selected frame.
+            let process = app.target.process();
+            editor.resolved = Pending::Running(app.worker.run(move |_| {
+                let frame = process.selected_thread().selected_frame();
+                debugger::watched_value(&frame, &target)
+            }));
+        } else {
+            editor.error = Some("The process must be stopped to evaluate the expression.".into());
+        }
+    }
+
+    if editor.resolved.poll().is_none() {
+        return;
+    }
+    let Pending::Done(resolved) = std::mem::replace(&mut editor.resolved, Pending::NotStarted)
+    else {
+        return;
+    };
+    let result = match resolved {
+        Ok((address, size, type_name)) => editor.options(size).and_then(|options| {
+            let watchpoint = debugger::create_watchpoint(&app.target, address, &options)
+                .map_err(|err| err.to_string())?;
+            let watched = WatchedValue {
+                name: editor
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| editor.target.trim().to_string()),
+                type_name,
+                kind: options.kind,
+            };
+            app.watched_values.insert(watchpoint.id(), watched);
+            let process = app.target.process();
+            if let Ok(value) = debugger::read_memory(&process, address, options.size) {
+                app.watch_memory.insert(watchpoint.id(), value);
+            }
+            Ok(())
+        }),
+        Err(err) => Err(err.to_string()),
+    };
+    match result {
+        Ok(()) => {
+            app.watchpoint_editor = None;
+            // Don't wait for the watchpoint event to show the change.
+            app.breakpoint_index_stale = true;
+        }
+        Err(err) => editor.error = Some(err),
+    }
+}
@@ -0,0 +1,148 @@
+const MAX_COMMANDS: usize = 1000;
+
+/// The commands entered in the console, oldest first. It is saved with the
+/// app so it is shared by all sessions of the user.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+pub struct ConsoleHistory {
+    commands: Vec<String>,
+    /// The command shown while navigating with up and down.
+    #[serde(skip)]
+    position: Option<usize>,
+    /// The input before navigating, restored when going down past the newest
+    /// command.
+    #[serde(skip)]
+    draft: String,
+    /// The reverse search, like Ctrl-R in a shell.
+    #[serde(skip)]
+    pub search: Option<HistorySearch>,
+}
+
+#[derive(Default)]
+pub struct HistorySearch {
+    pub query: String,
+    /// The index of the matching command.
+    pub found: Option<usize>,
+}
+
+impl ConsoleHistory {
+    pub fn push(&mut self, command: &str) {
+        self.position = None;
+        if command.is_empty() || self.commands.last().is_some_and(|last| last == command) {
+            return;
+        }
+        self.commands.push(command.to_string());
+        if self.commands.len() > MAX_COMMANDS {
+            self.commands.remove(0);
+        }
+    }
+
+    /// Returns the command before the one shown, `input` is kept to go back
+    /// to it.
+    pub fn previous(&mut self, input: &str) -> Option<&str> {
+        let position = match self.position {
+            None => {
+                self.draft = input.to_string();
+                self.commands.len().checked_sub(1)?
+            }
+            Some(position) => position.saturating_sub(1),
+        };
+        self.position = Some(position);
+        self.commands.get(position).map(String::as_str)
+    }
+
+    /// Returns the command after the one shown, or the input before the
+    /// navigation started.
+    pub fn next(&mut self) -> Option<&str> {
+        let position = self.position?;
+        if position + 1 < self.commands.len() {
+            self.position = Some(position + 1);
+            self.commands.get(position + 1).map(String::as_str)
+        } else {
+            self.position = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// Finds the newest command containing the query of the search. With
+    /// `older` the search continues before the current match.
+    pub fn find(&mut self, older: bool) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        let end = match search.found {
+            Some(found) if older => found,
+            _ => self.commands.len(),
+        };
+        let found = self.commands[..end]
+            .iter()
+            .rposition(|command| command.contains(&search.query));
+        // Keep the current match if there is no older one.
+        if found.is_some() || !older {
+            search.found = found;
+        }
+    }
+
+    /// The command found by the search.
+    pub fn found(&self) -> Option<&str> {
+        let found = self.search.as_ref()?.found?;
+        self.commands.get(found).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(commands: &[&str]) -> ConsoleHistory {
+        let mut history = ConsoleHistory::default();
+        for command in commands {
+            history.push(command);
+        }
+        history
+    }
+
+    #[test]
+    fn skips_empty_and_repeated_commands() {
+        let history = history(&["next", "", "next", "bt"]);
+        assert_eq!(history.commands, ["next", "bt"]);
+    }
+
+    #[test]
+    fn navigates_and_restores_the_draft() {
+        let mut history = history(&["a", "b"]);
+        assert_eq!(history.next(), None);
+        assert_eq!(history.previous("dra"), Some("b"));
+        assert_eq!(history.previous("ignored"), Some("a"));
+        // Stays at the oldest command.
+        assert_eq!(history.previous("ignored"), Some("a"));
+        assert_eq!(history.next(), Some("b"));
+        assert_eq!(history.next(), Some("dra"));
+        assert_eq!(history.next(), None);
+        // Entering a command starts over at the newest one.
+        history.previous("");
+        history.push("c");
+        assert_eq!(history.previous(""), Some("c"));
+    }
+
+    #[test]
+    fn searches_older_matches() {
+        let mut history = history(&["frame variable", "bt", "frame select 1"]);
+        history.search = Some(HistorySearch {
+            query: "frame".to_string(),
+            found: None,
+        });
+        history.find(false);
+        assert_eq!(history.found(), Some("frame select 1"));
+        history.find(true);
+        assert_eq!(history.found(), Some("frame variable"));
+        // Without an older match the current one is kept.
+        history.find(true);
+        assert_eq!(history.found(), Some("frame variable"));
+
+        if let Some(search) = &mut history.search {
+            search.query = "nothing".to_string();
+        }
+        history.find(false);
+        assert_eq!(history.found(), None);
+    }
+}
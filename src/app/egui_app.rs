@@ -1,14 +1,57 @@
 use egui::{CentralPanel, SidePanel, TopBottomPanel};
+use lldb::StateType;
 
 use crate::app::components;
-use crate::app::App;
+use crate::app::{
+    App, CONSOLE_HISTORY_KEY, RECENT_SESSIONS_KEY, SAVED_SESSIONS_KEY, SOURCE_MAPS_KEY,
+};
+use crate::debugger;
 
 impl eframe::App for App {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, RECENT_SESSIONS_KEY, &self.recent_sessions);
+        eframe::set_value(storage, SOURCE_MAPS_KEY, &self.source_maps);
+        self.save_session();
+        eframe::set_value(storage, SAVED_SESSIONS_KEY, &self.saved_sessions);
+        eframe::set_value(storage, CONSOLE_HISTORY_KEY, &self.console_history);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.event_thread.shutdown();
+        self.worker.shutdown();
+    }
+
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         self.frame_history
             .on_new_frame(ctx.input(|i| i.time), frame.info().cpu_usage);
 
+        self.handle_events();
+        self.update_snapshot();
+
         components::close_confirmation(self, ctx);
+        components::launch_dialog(self, ctx);
+        components::breakpoint_editor(self, ctx);
+        components::watchpoint_editor(self, ctx);
+        components::notifications(self, ctx);
+
+        if self.start_screen.is_some() {
+            TopBottomPanel::bottom("bottom_bar").show(ctx, |ui| components::bottom_bar(self, ui));
+            CentralPanel::default().show(ctx, |ui| components::start_screen(self, ui));
+            return;
+        }
+
+        if !self.pending_commands.is_empty() && self.process_state == StateType::Stopped {
+            debugger::execute_startup_commands(
+                &self.target.debugger(),
+                &self.pending_commands,
+                &mut self.console_output,
+            );
+            self.pending_commands.clear();
+            // The commands are executed synchronously, their events might not
+            // be seen.
+            self.process_state = self.target.process().state();
+            self.invalidate_snapshot();
+        }
 
         TopBottomPanel::bottom("bottom_bar").show(ctx, |ui| components::bottom_bar(self, ui));
         SidePanel::left("left_panel")
@@ -38,8 +81,17 @@ impl eframe::App for App {
                 components::console_tabs(self, ui);
             });
 
+        if self.show_sources {
+            SidePanel::right("sources_panel")
+                .resizable(true)
+                .show(ctx, |ui| components::sources(self, ui));
+        }
+
         CentralPanel::default().show(ctx, |ui| {
-            if self.target.process().is_stopped() {
+            if self.process_state == StateType::Attaching {
+                components::attach_progress(self, ui)
+            } else {
+                components::stop_banner(self, ui);
                 components::source_view(self, ui)
             }
         });
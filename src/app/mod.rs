@@ -1,44 +1,136 @@
 mod components;
+mod console_history;
 mod egui_app;
 mod frame_history;
+mod notifications;
+mod saved_session;
+mod snapshot;
+mod syntax;
 mod widgets;
+mod worker;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
 use std::str::FromStr;
-use std::time::Duration;
-use std::{thread, thread::JoinHandle};
+use std::sync::mpsc::Receiver;
 
 use eframe::CreationContext;
-use egui::{style::ScrollStyle, Context};
-use lldb::{SBEvent, SBListener, SBTarget};
+use egui::style::ScrollStyle;
+use lldb::{SBBreakpoint, SBTarget, StateType};
 
+use crate::app::components::{
+    BreakpointEditor, BreakpointForm, BreakpointsFile, FileBrowser, LaunchDialog, StartScreen,
+    WatchpointEditor,
+};
+use crate::app::console_history::ConsoleHistory;
 use crate::app::frame_history::FrameHistory;
+use crate::app::notifications::Notifications;
+use crate::app::saved_session::{SavedBreakpoints, SavedSession, SavedWatchpoint};
+use crate::app::snapshot::{
+    BreakpointIndex, ModuleSources, Snapshot, StopUpdate, WatchExpression, WatchedValue,
+    WatchpointChange,
+};
+use crate::app::worker::{Pending, Worker};
+use crate::debugger::{self, BreakpointCommands, BreakpointSpec, LaunchOptions, ProcessExit};
+use crate::events::{Change, DebuggerEvent, EventThread};
 use crate::resources;
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Default, PartialEq, serde::Deserialize, serde::Serialize)]
 enum ConsoleTab {
+    #[default]
     Console,
     Stdout,
     Stderr,
+    Log,
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Default, PartialEq, serde::Deserialize, serde::Serialize)]
 enum VariablesTab {
+    #[default]
     Locals,
     Statics,
     Arguments,
     Registers,
+    Watch,
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Default, PartialEq, serde::Deserialize, serde::Serialize)]
 enum BreakpointsTab {
+    #[default]
     Breakpoints,
     Watchpoints,
 }
 
+/// The debug session the UI is opened with.
+pub struct Session {
+    pub target: SBTarget,
+    /// The options the process was launched with.
+    pub launch_options: Option<LaunchOptions>,
+    /// The breakpoint set to run the launched process to `main`.
+    pub run_to_main_breakpoint: Option<i32>,
+    /// Launch the process of the target once the saved session is restored.
+    pub launch: bool,
+    /// The core file the target was loaded from.
+    pub core_file: Option<String>,
+    /// Description of the remote connection for remote sessions.
+    pub remote: Option<String>,
+    /// Output of the commands executed on startup.
+    pub console_output: String,
+    /// Commands to execute once a pending attach has finished or a target was
+    /// picked on the start screen.
+    pub pending_commands: Vec<String>,
+    /// Show the start screen to pick a target, `target` is a placeholder.
+    pub start_screen: bool,
+}
+
+/// A previously started debug session, listed on the start screen.
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum RecentSession {
+    Launch {
+        executable: String,
+        args: Vec<String>,
+    },
+    Core {
+        executable: String,
+        core: String,
+    },
+}
+
+const RECENT_SESSIONS_KEY: &str = "recent_sessions";
+const MAX_RECENT_SESSIONS: usize = 10;
+const SOURCE_MAPS_KEY: &str = "source_maps";
+const SAVED_SESSIONS_KEY: &str = "saved_sessions";
+const CONSOLE_HISTORY_KEY: &str = "console_history";
+
+/// Source path prefix mappings (old, new) by executable path.
+type SourceMaps = HashMap<String, Vec<(String, String)>>;
+
+/// A source file that couldn't be found and is being located by the user.
+struct LocateSource {
+    missing: PathBuf,
+    file_browser: FileBrowser,
+}
+
 pub struct App {
     target: SBTarget,
+    launch_options: Option<LaunchOptions>,
+    /// The breakpoint set to run the launched process to `main`. It is hidden
+    /// and deleted after the first stop, the id is kept until the next launch
+    /// to recognize the stop at `main`.
+    run_to_main_breakpoint: Option<i32>,
+    core_file: Option<String>,
+    remote: Option<String>,
+    pending_commands: Vec<String>,
+    start_screen: Option<StartScreen>,
+    recent_sessions: Vec<RecentSession>,
+    source_maps: SourceMaps,
+    /// Breakpoints, watch expressions and tabs by executable path.
+    saved_sessions: HashMap<String, SavedSession>,
+    event_thread: EventThread,
+    events: Receiver<DebuggerEvent>,
+    worker: Worker,
     frame_history: FrameHistory,
+    notifications: Notifications,
 
     console_tab: ConsoleTab,
     variables_tab: VariablesTab,
@@ -46,31 +138,124 @@ pub struct App {
 
     show_confirmation_dialog: bool,
     allowed_to_close: bool,
+    launch_dialog: Option<LaunchDialog>,
+    breakpoint_editor: Option<BreakpointEditor>,
+    breakpoint_form: BreakpointForm,
+    breakpoints_file: Option<BreakpointsFile>,
+    watchpoint_editor: Option<WatchpointEditor>,
+
+    /// The state of the process as of the last event. Asking LLDB for it on
+    /// every frame would block the UI while the worker runs an expression.
+    process_state: StateType,
+    snapshot: Option<Snapshot>,
+    /// The snapshot being built on the worker.
+    snapshot_update: Pending<Option<Snapshot>>,
+    /// The snapshot is shown until it was built again, e.g. after the
+    /// selected thread changed.
+    snapshot_stale: bool,
+    breakpoint_index: Option<BreakpointIndex>,
+    breakpoint_index_update: Pending<BreakpointIndex>,
+    breakpoint_index_stale: bool,
+    /// The stops being handled on the worker in the order they happened, e.g.
+    /// formatting the messages of the logpoints the process stopped at.
+    stop_updates: VecDeque<Pending<StopUpdate>>,
+    watch_expressions: Vec<WatchExpression>,
+    watch_input: String,
+    /// Message templates of the logpoints by breakpoint id.
+    logpoints: HashMap<i32, String>,
+    /// How the breakpoints created in the app were set by breakpoint id, lldb
+    /// only describes them as text.
+    breakpoint_specs: HashMap<i32, BreakpointSpec>,
+    /// Commands attached to the breakpoints by breakpoint id.
+    breakpoint_commands: HashMap<i32, BreakpointCommands>,
+    /// Watchpoints of the saved session waiting for a process to be set in.
+    pending_watchpoints: Vec<SavedWatchpoint>,
+    /// What the watchpoints were set on by watchpoint id.
+    watched_values: HashMap<i32, WatchedValue>,
+    /// The watched memory as it was at the last stop by watchpoint id.
+    watch_memory: HashMap<i32, Vec<u8>>,
+    /// The watchpoints that were enabled while the process was last alive, to
+    /// enable them again on restart.
+    enabled_watchpoints: Vec<i32>,
+    /// Set if the process stopped at a watchpoint.
+    watchpoint_change: Option<WatchpointChange>,
+    /// Set once the process exited, until it is launched again.
+    process_exit: Option<ProcessExit>,
 
-    source_cache: HashMap<String, String>,
+    /// The contents of the source files by path, or why they couldn't be read.
+    source_cache: HashMap<String, Result<String, String>>,
     source_file: String,
     source_line: u32,
+    locate_source: Option<LocateSource>,
+    /// Files opened from the sources browser, shown in tabs next to the
+    /// source of the selected frame.
+    open_sources: Vec<PathBuf>,
+    /// The selected file tab, `None` shows the source of the selected frame.
+    source_tab: Option<usize>,
+    show_sources: bool,
+    sources: Pending<Vec<ModuleSources>>,
+    sources_filter: String,
+    /// Languages picked by the user to highlight source files with, by path.
+    language_overrides: HashMap<String, String>,
 
     process_stdout: String,
     process_stderr: String,
 
     console_input: String,
+    console_history: ConsoleHistory,
     console_output: String,
+    /// The console command that is running on the worker.
+    console_command: Pending<String>,
+    log_output: String,
 }
 
 impl App {
-    pub fn new(cc: &CreationContext<'_>, target: SBTarget) -> Self {
+    pub fn new(cc: &CreationContext<'_>, session: Session) -> Self {
         cc.egui_ctx.set_fonts(resources::load_fonts());
         let mut style = (*cc.egui_ctx.style()).clone();
         style.spacing.scroll = ScrollStyle::solid();
         resources::register_fonts(&mut style);
         cc.egui_ctx.set_style(style);
 
-        handle_lldb_events_thread(cc.egui_ctx.clone(), target.debugger().listener().clone());
+        let (event_thread, events) = EventThread::spawn(
+            cc.egui_ctx.clone(),
+            session.target.debugger().listener().clone(),
+        );
 
-        Self {
-            target,
+        let recent_sessions = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, RECENT_SESSIONS_KEY))
+            .unwrap_or_default();
+        let source_maps = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, SOURCE_MAPS_KEY))
+            .unwrap_or_default();
+        let saved_sessions = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, SAVED_SESSIONS_KEY))
+            .unwrap_or_default();
+        let console_history = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, CONSOLE_HISTORY_KEY))
+            .unwrap_or_default();
+
+        let process_state = session.target.process().state();
+        let mut app = Self {
+            target: session.target,
+            launch_options: session.launch_options,
+            run_to_main_breakpoint: session.run_to_main_breakpoint,
+            core_file: session.core_file,
+            remote: session.remote,
+            pending_commands: session.pending_commands,
+            start_screen: session.start_screen.then(StartScreen::default),
+            recent_sessions,
+            source_maps,
+            saved_sessions,
+            event_thread,
+            events,
+            worker: Worker::spawn(cc.egui_ctx.clone()),
             frame_history: FrameHistory::default(),
+            notifications: Notifications::default(),
 
             console_tab: ConsoleTab::Console,
             variables_tab: VariablesTab::Locals,
@@ -78,31 +263,436 @@ impl App {
 
             show_confirmation_dialog: false,
             allowed_to_close: false,
+            launch_dialog: None,
+            breakpoint_editor: None,
+            breakpoint_form: BreakpointForm::default(),
+            breakpoints_file: None,
+            watchpoint_editor: None,
+
+            process_state,
+            snapshot: None,
+            snapshot_update: Pending::NotStarted,
+            snapshot_stale: false,
+            breakpoint_index: None,
+            breakpoint_index_update: Pending::NotStarted,
+            breakpoint_index_stale: false,
+            stop_updates: VecDeque::new(),
+            watch_expressions: Vec::new(),
+            watch_input: String::new(),
+            logpoints: HashMap::new(),
+            breakpoint_specs: HashMap::new(),
+            breakpoint_commands: HashMap::new(),
+            pending_watchpoints: Vec::new(),
+            watched_values: HashMap::new(),
+            watch_memory: HashMap::new(),
+            enabled_watchpoints: Vec::new(),
+            watchpoint_change: None,
+            process_exit: None,
 
             source_cache: HashMap::new(),
             source_file: String::new(),
             source_line: 0,
+            locate_source: None,
+            open_sources: Vec::new(),
+            source_tab: None,
+            show_sources: true,
+            sources: Pending::NotStarted,
+            sources_filter: String::new(),
+            language_overrides: HashMap::new(),
 
             process_stdout: String::new(),
             process_stderr: String::new(),
 
             console_input: String::new(),
-            console_output: String::from_str("\n\n").unwrap(),
+            console_history,
+            console_output: String::from_str("\n\n").unwrap() + &session.console_output,
+            console_command: Pending::NotStarted,
+            log_output: String::new(),
+        };
+        app.add_recent_session();
+        app.apply_source_maps();
+        app.restore_session();
+        if session.launch {
+            app.launch();
         }
+        app
     }
-}
 
-// Used to force a repaint when the UI needs to update without user interaction.
-// For example when new data from stdout of the debugged process is available.
-pub fn handle_lldb_events_thread(egui_ctx: Context, listener: SBListener) -> JoinHandle<()> {
-    thread::spawn(move || {
-        let event = SBEvent::new();
-        loop {
-            listener.wait_for_event(1, &event);
-            if event.is_valid() {
-                tracing::debug!("LLDB event: {:?}", event);
-                egui_ctx.request_repaint_after(Duration::from_millis(100));
+    fn handle_events(&mut self) {
+        while let Some(update) = self.stop_updates.front_mut().and_then(Pending::take) {
+            self.stop_updates.pop_front();
+            self.handle_stop(update);
+        }
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                DebuggerEvent::ProcessStateChanged { state, exit } => {
+                    self.process_state = state;
+                    // Output events can get lost while the debugger runs
+                    // synchronously, catch up on every state change.
+                    self.read_process_output();
+                    self.snapshot = None;
+                    self.snapshot_update.reset();
+                    self.process_exit = None;
+                    if matches!(state, StateType::Stopped | StateType::Exited) {
+                        self.delete_run_to_main_breakpoint();
+                    }
+                    match state {
+                        StateType::Exited => {
+                            if let Some(exit) = exit {
+                                self.console_output.push_str(&format!(
+                                    "Process exited with status = {}\n",
+                                    exit.status
+                                ));
+                                self.notifications
+                                    .info(format!("Process {}", exit.summary()));
+                                self.process_exit = Some(exit);
+                            }
+                        }
+                        StateType::Stopped => {
+                            // The stop isn't shown before the worker handled it.
+                            let target = self.target.clone();
+                            let logpoints = self.logpoints.clone();
+                            self.stop_updates.push_back(Pending::Running(
+                                self.worker
+                                    .run(move |_| StopUpdate::new(&target, &logpoints)),
+                            ));
+                        }
+                        _ => {}
+                    }
+                }
+                DebuggerEvent::StdoutAvailable | DebuggerEvent::StderrAvailable => {
+                    self.read_process_output()
+                }
+                DebuggerEvent::ModulesLoaded | DebuggerEvent::ModulesUnloaded => {
+                    // The sources might have changed with a rebuilt module.
+                    self.source_cache.clear();
+                    self.sources.reset();
+                    // Breakpoints might have been resolved in the new modules.
+                    self.breakpoint_index_stale = true;
+                }
+                DebuggerEvent::BreakpointChanged { id, change } => {
+                    tracing::debug!("breakpoint {} changed: {:?}", id, change);
+                    // Changes of the options are shown in the breakpoints
+                    // panel, only notify about the ones that are easy to miss,
+                    // e.g. from a console command, and not about every
+                    // breakpoint the app created or restored itself.
+                    let notify = match change {
+                        Change::Modified => false,
+                        Change::Removed => Some(id) != self.run_to_main_breakpoint,
+                        Change::Added | Change::Resolved => {
+                            !self.breakpoint_specs.contains_key(&id)
+                                && Some(id) != self.run_to_main_breakpoint
+                        }
+                    };
+                    if notify {
+                        self.notifications
+                            .info(format!("Breakpoint {} {}", id, change.label()));
+                    }
+                    if change == Change::Removed {
+                        self.logpoints.remove(&id);
+                        self.breakpoint_specs.remove(&id);
+                        self.breakpoint_commands.remove(&id);
+                    }
+                    self.breakpoint_index_stale = true;
+                }
+                DebuggerEvent::WatchpointChanged { id, change } => {
+                    tracing::debug!("watchpoint {} changed: {:?}", id, change);
+                    let notify = match change {
+                        Change::Modified => false,
+                        Change::Removed => true,
+                        Change::Added | Change::Resolved => !self.watched_values.contains_key(&id),
+                    };
+                    if notify {
+                        self.notifications
+                            .info(format!("Watchpoint {} {}", id, change.label()));
+                    }
+                    match change {
+                        // New watchpoints are enabled.
+                        Change::Added => self.enabled_watchpoints.push(id),
+                        Change::Removed => {
+                            self.watched_values.remove(&id);
+                            self.watch_memory.remove(&id);
+                            self.enabled_watchpoints.retain(|enabled| *enabled != id);
+                        }
+                        _ => {}
+                    }
+                    self.breakpoint_index_stale = true;
+                }
+                DebuggerEvent::ThreadSelectionChanged => {
+                    // The selection might have changed in the console.
+                    self.invalidate_snapshot();
+                }
             }
         }
-    })
+    }
+
+    /// Shows the stop once the worker handled it.
+    fn handle_stop(&mut self, update: StopUpdate) {
+        if !update.log_messages.is_empty() {
+            for message in update.log_messages {
+                self.log_output.push_str(&message);
+                self.log_output.push('\n');
+            }
+            // The process was continued, unless that failed and it is still
+            // stopped at the logpoints.
+            let Some(err) = update.continue_error else {
+                return;
+            };
+            self.notifications
+                .error(format!("Failed to continue after logpoint: {}", err));
+        }
+        self.source_file.clear(); // reset to make the source view scroll
+        self.source_tab = None;
+        // Update the hit counts.
+        self.breakpoint_index_stale = true;
+        self.restore_pending_watchpoints();
+        self.watchpoint_change = None;
+        self.enabled_watchpoints = update.enabled_watchpoints;
+        for (id, new_value) in update.watch_memory {
+            let old_value = self.watch_memory.insert(id, new_value.clone());
+            if update.watchpoint_hit == Some(id) {
+                self.watchpoint_change = Some(WatchpointChange {
+                    id,
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+    }
+
+    /// Builds the snapshot and breakpoint index on the worker if they are
+    /// missing or were invalidated.
+    fn update_snapshot(&mut self) {
+        if let Some(snapshot) = self.snapshot_update.take() {
+            if snapshot.is_some() {
+                // Evaluate the watch expressions again in the new context.
+                for watch_expression in &mut self.watch_expressions {
+                    watch_expression.value.reset();
+                }
+            }
+            self.snapshot = snapshot;
+        }
+        // Don't show the stop before the worker handled it, e.g. the process
+        // continues right away at a logpoint.
+        if self.process_state != StateType::Stopped || !self.stop_updates.is_empty() {
+            self.snapshot = None;
+            self.snapshot_update.reset();
+        } else if (self.snapshot.is_none() || self.snapshot_stale)
+            && !self.snapshot_update.is_running()
+        {
+            self.snapshot_stale = false;
+            let process = self.target.process();
+            self.snapshot_update =
+                Pending::Running(self.worker.run(move |_| Snapshot::new(&process)));
+        }
+
+        if let Some(index) = self.breakpoint_index_update.take() {
+            self.breakpoint_index = Some(index);
+        }
+        if (self.breakpoint_index.is_none() || self.breakpoint_index_stale)
+            && !self.breakpoint_index_update.is_running()
+        {
+            self.breakpoint_index_stale = false;
+            let target = self.target.clone();
+            let hidden = self.run_to_main_breakpoint;
+            let logpoints = self.logpoints.clone();
+            let commands = self.breakpoint_commands.clone();
+            self.breakpoint_index_update = Pending::Running(
+                self.worker
+                    .run(move |_| BreakpointIndex::new(&target, hidden, &logpoints, &commands)),
+            );
+        }
+    }
+
+    /// Creates the breakpoint and remembers how, to save it with the session.
+    fn create_breakpoint(&mut self, spec: &BreakpointSpec) -> anyhow::Result<SBBreakpoint> {
+        let breakpoint = debugger::create_breakpoint_from_spec(&self.target, spec)?;
+        self.breakpoint_specs.insert(breakpoint.id(), spec.clone());
+        Ok(breakpoint)
+    }
+
+    /// Launches the process of a target created for launching it, after the
+    /// saved session was restored so its breakpoints are already set.
+    fn launch(&mut self) {
+        let result = debugger::launch_target(&self.target);
+        if let Some(run_to_main) = self.notifications.report("Failed to launch", result) {
+            self.run_to_main_breakpoint = run_to_main;
+        }
+        // The launch is synchronous, its events might not be seen.
+        self.process_state = self.target.process().state();
+        self.restore_pending_watchpoints();
+    }
+
+    /// Launches the process again, with new launch options if given.
+    fn relaunch(&mut self, options: Option<&LaunchOptions>) -> anyhow::Result<()> {
+        self.delete_run_to_main_breakpoint();
+        self.run_to_main_breakpoint = match options {
+            Some(options) => debugger::launch(&self.target, options, &self.enabled_watchpoints)?,
+            None => debugger::restart(&self.target, &self.enabled_watchpoints)?,
+        };
+        // The launch is synchronous, its events might not be seen.
+        self.process_state = self.target.process().state();
+        self.restore_pending_watchpoints();
+        Ok(())
+    }
+
+    /// Sets the watchpoints of the saved session that were waiting for a
+    /// process.
+    fn restore_pending_watchpoints(&mut self) {
+        if self.pending_watchpoints.is_empty()
+            || !debugger::process_is_alive(&self.target.process())
+        {
+            return;
+        }
+        for saved in std::mem::take(&mut self.pending_watchpoints) {
+            saved.restore(self);
+        }
+        self.breakpoint_index_stale = true;
+    }
+
+    fn delete_run_to_main_breakpoint(&self) {
+        if let Some(id) = self.run_to_main_breakpoint {
+            // Nothing happens if it was deleted already or was hit, as it
+            // is a one-shot breakpoint.
+            self.target.delete_breakpoint(id);
+        }
+    }
+
+    /// Forces the snapshot to be rebuilt, e.g. after the selection changed or
+    /// a command was executed that might have changed the process state.
+    fn invalidate_snapshot(&mut self) {
+        self.snapshot_stale = true;
+        self.breakpoint_index_stale = true;
+        self.source_file.clear(); // reset to make the source view scroll
+    }
+
+    fn read_process_output(&mut self) {
+        let process = self.target.process();
+        if let Some(output) = process.get_stdout_all() {
+            self.process_stdout.push_str(&output);
+        }
+        // TODO(ds): somehow stderr of the process ends up in stdout and this is always empty?
+        // https://github.com/llvm/llvm-project/issues/25350#issuecomment-980951241
+        if let Some(output) = process.get_stderr() {
+            self.process_stderr.push_str(&output);
+        }
+    }
+
+    /// Adds the current session to the top of the recent sessions.
+    fn add_recent_session(&mut self) {
+        let Some(executable) = debugger::executable_path(&self.target) else {
+            return;
+        };
+        let session = if let Some(core) = &self.core_file {
+            RecentSession::Core {
+                executable,
+                core: core.clone(),
+            }
+        } else if let Some(launch_options) = &self.launch_options {
+            RecentSession::Launch {
+                executable,
+                args: launch_options.args.clone(),
+            }
+        } else {
+            return;
+        };
+        self.recent_sessions.retain(|s| *s != session);
+        self.recent_sessions.insert(0, session);
+        self.recent_sessions.truncate(MAX_RECENT_SESSIONS);
+    }
+
+    /// Applies the source maps remembered for the executable of the target.
+    fn apply_source_maps(&mut self) {
+        let Some(executable) = debugger::executable_path(&self.target) else {
+            return;
+        };
+        let debugger = self.target.debugger();
+        for (old, new) in self.source_maps.get(&executable).into_iter().flatten() {
+            if let Err(err) = debugger::add_source_map(&debugger, old, new) {
+                self.notifications
+                    .error(format!("Failed to apply source map: {}", err));
+            }
+        }
+    }
+
+    /// Maps source paths starting with `old` to `new` and remembers the
+    /// mapping for the executable.
+    fn add_source_map(&mut self, old: String, new: String) {
+        if let Err(err) = debugger::add_source_map(&self.target.debugger(), &old, &new) {
+            self.notifications
+                .error(format!("Failed to add source map: {}", err));
+            return;
+        }
+        if let Some(executable) = debugger::executable_path(&self.target) {
+            self.source_maps
+                .entry(executable)
+                .or_default()
+                .push((old, new));
+        }
+        self.invalidate_snapshot();
+    }
+
+    /// Restores the breakpoints, watch expressions and tabs of the last
+    /// session of the executable.
+    fn restore_session(&mut self) {
+        let Some(executable) = debugger::executable_path(&self.target) else {
+            return;
+        };
+        // It is saved again with the current state in `save_session`.
+        let Some(session) = self.saved_sessions.remove(&executable) else {
+            return;
+        };
+        session.breakpoints.restore(self);
+        self.watch_expressions = session
+            .watch_expressions
+            .into_iter()
+            .map(WatchExpression::new)
+            .collect();
+        self.open_sources = session.open_sources;
+        self.source_tab = session
+            .source_tab
+            .filter(|tab| *tab < self.open_sources.len());
+        self.console_tab = session.console_tab;
+        self.variables_tab = session.variables_tab;
+        self.breakpoints_tab = session.breakpoints_tab;
+        self.breakpoint_index_stale = true;
+    }
+
+    /// Remembers the state of the session for the executable, it is restored
+    /// when the executable is debugged again.
+    fn save_session(&mut self) {
+        let Some(executable) = debugger::executable_path(&self.target) else {
+            return;
+        };
+        let session = SavedSession {
+            breakpoints: self.saved_breakpoints(),
+            watch_expressions: self
+                .watch_expressions
+                .iter()
+                .map(|watch_expression| watch_expression.expression.clone())
+                .collect(),
+            open_sources: self.open_sources.clone(),
+            source_tab: self.source_tab,
+            console_tab: self.console_tab,
+            variables_tab: self.variables_tab,
+            breakpoints_tab: self.breakpoints_tab,
+        };
+        self.saved_sessions.insert(executable, session);
+    }
+
+    fn saved_breakpoints(&self) -> SavedBreakpoints {
+        SavedBreakpoints::new(self)
+    }
+
+    /// Opens the source file in a tab, or selects its tab if it is open already.
+    fn open_source(&mut self, path: PathBuf) {
+        let i = match self.open_sources.iter().position(|open| *open == path) {
+            Some(i) => i,
+            None => {
+                self.open_sources.push(path);
+                self.open_sources.len() - 1
+            }
+        };
+        self.source_tab = Some(i);
+    }
 }
@@ -0,0 +1,73 @@
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+/// How long a notification is shown as a toast.
+pub const TOAST_DURATION: Duration = Duration::from_secs(6);
+const MAX_HISTORY: usize = 100;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Level {
+    Info,
+    Error,
+}
+
+pub struct Notification {
+    pub level: Level,
+    pub message: String,
+    pub time: Instant,
+    /// Dismissed notifications are only listed in the history.
+    pub dismissed: bool,
+}
+
+/// Messages for the user, e.g. errors of LLDB calls. They are shown as toasts
+/// and kept in a history.
+#[derive(Default)]
+pub struct Notifications {
+    pub history: Vec<Notification>,
+    pub show_history: bool,
+}
+
+impl Notifications {
+    pub fn info(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        tracing::info!("{}", message);
+        self.push(Level::Info, message);
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        tracing::error!("{}", message);
+        self.push(Level::Error, message);
+    }
+
+    /// Notifies about the error of a failed call, `action` describes what
+    /// failed, e.g. "Failed to step over".
+    pub fn report<T, E: Display>(&mut self, action: &str, result: Result<T, E>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.error(format!("{}: {}", action, err));
+                None
+            }
+        }
+    }
+
+    /// The notifications to show as toasts, newest last.
+    pub fn toasts(&mut self) -> impl Iterator<Item = &mut Notification> {
+        self.history.iter_mut().filter(|notification| {
+            !notification.dismissed && notification.time.elapsed() < TOAST_DURATION
+        })
+    }
+
+    fn push(&mut self, level: Level, message: String) {
+        self.history.push(Notification {
+            level,
+            message,
+            time: Instant::now(),
+            dismissed: false,
+        });
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use lldb::SBBreakpoint;
+
+use crate::app::snapshot::WatchedValue;
+use crate::app::{App, BreakpointsTab, ConsoleTab, VariablesTab};
+use crate::debugger::{
+    self, BreakpointCommands, BreakpointOptions, BreakpointSpec, WatchpointOptions,
+};
+
+/// The state of a debug session that is restored the next time the same
+/// executable is debugged.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+pub struct SavedSession {
+    pub breakpoints: SavedBreakpoints,
+    pub watch_expressions: Vec<String>,
+    pub open_sources: Vec<PathBuf>,
+    pub source_tab: Option<usize>,
+    pub console_tab: ConsoleTab,
+    pub variables_tab: VariablesTab,
+    pub breakpoints_tab: BreakpointsTab,
+}
+
+/// Breakpoints and watchpoints as they are saved with the session and
+/// exported to share them.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+pub struct SavedBreakpoints {
+    pub breakpoints: Vec<SavedBreakpoint>,
+    pub watchpoints: Vec<SavedWatchpoint>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct SavedBreakpoint {
+    pub spec: BreakpointSpec,
+    pub options: BreakpointOptions,
+    pub log_message: Option<String>,
+    pub commands: Option<BreakpointCommands>,
+}
+
+/// A watchpoint on the address. Without ASLR disabled the address might not
+/// point to the same variable in the next session.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct SavedWatchpoint {
+    pub address: u64,
+    pub size: usize,
+    #[serde(default)]
+    pub condition: Option<String>,
+    #[serde(default)]
+    pub ignore_count: u32,
+    #[serde(default)]
+    pub watched: Option<WatchedValue>,
+}
+
+impl SavedBreakpoints {
+    /// Collects the breakpoints and watchpoints of the target, together with
+    /// what the app knows about them, e.g. the logpoint messages.
+    pub fn new(app: &App) -> Self {
+        let breakpoints = app
+            .target
+            .breakpoints()
+            .filter(|breakpoint| Some(breakpoint.id()) != app.run_to_main_breakpoint)
+            .filter_map(|breakpoint| {
+                let Some(spec) = breakpoint_spec(app, &breakpoint) else {
+                    tracing::warn!("Can't save breakpoint: {:?}", breakpoint);
+                    return None;
+                };
+                Some(SavedBreakpoint {
+                    spec,
+                    options: debugger::breakpoint_options(&breakpoint),
+                    log_message: app.logpoints.get(&breakpoint.id()).cloned(),
+                    commands: app.breakpoint_commands.get(&breakpoint.id()).cloned(),
+                })
+            })
+            .collect();
+        let watchpoints = app
+            .target
+            .watchpoints()
+            .map(|watchpoint| SavedWatchpoint {
+                address: watchpoint.watch_address(),
+                size: watchpoint.watch_size(),
+                condition: watchpoint.condition().map(String::from),
+                ignore_count: watchpoint.ignore_count(),
+                watched: app.watched_values.get(&watchpoint.id()).cloned(),
+            })
+            .chain(app.pending_watchpoints.iter().cloned())
+            .collect();
+        Self {
+            breakpoints,
+            watchpoints,
+        }
+    }
+
+    /// Creates the breakpoints and watchpoints in the target. Breakpoints that
+    /// exist already, e.g. from the startup commands, aren't created twice.
+    /// Watchpoints need a running process, without one they are set once the
+    /// process is launched or stops.
+    pub fn restore(&self, app: &mut App) {
+        let existing: Vec<BreakpointSpec> = app
+            .target
+            .breakpoints()
+            .filter_map(|breakpoint| breakpoint_spec(app, &breakpoint))
+            .collect();
+        for saved in &self.breakpoints {
+            if existing.contains(&saved.spec) {
+                continue;
+            }
+            match app.create_breakpoint(&saved.spec) {
+                Ok(breakpoint) => {
+                    debugger::set_breakpoint_options(&breakpoint, &saved.options);
+                    if let Some(log_message) = &saved.log_message {
+                        app.logpoints.insert(breakpoint.id(), log_message.clone());
+                    }
+                    if let Some(commands) = &saved.commands {
+                        if let Err(err) = debugger::set_breakpoint_commands(&breakpoint, commands) {
+                            app.notifications
+                                .error(format!("Failed to restore breakpoint commands: {}", err));
+                        }
+                        app.breakpoint_commands
+                            .insert(breakpoint.id(), commands.clone());
+                    }
+                }
+                Err(err) => app
+                    .notifications
+                    .error(format!("Failed to restore breakpoint: {}", err)),
+            }
+        }
+
+        if !debugger::process_is_alive(&app.target.process()) {
+            app.pending_watchpoints
+                .extend(self.watchpoints.iter().cloned());
+            return;
+        }
+        for saved in &self.watchpoints {
+            saved.restore(app);
+        }
+    }
+
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn import(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+impl SavedWatchpoint {
+    /// Creates the watchpoint in the target unless there is one on the
+    /// address already.
+    pub fn restore(&self, app: &mut App) {
+        let target = &app.target;
+        if target
+            .watchpoints()
+            .any(|watchpoint| watchpoint.watch_address() == self.address)
+        {
+            return;
+        }
+        let options = WatchpointOptions {
+            size: self.size,
+            kind: self.watched.as_ref().map(|w| w.kind).unwrap_or_default(),
+            condition: self.condition.clone(),
+            ignore_count: self.ignore_count,
+        };
+        match debugger::create_watchpoint(target, self.address, &options) {
+            Ok(watchpoint) => {
+                // Also recorded without the saved value so the app knows it
+                // created the watchpoint, e.g. to not notify about it.
+                let watched = self.watched.clone().unwrap_or_else(|| WatchedValue {
+                    name: format!("{:#x}", self.address),
+                    type_name: String::new(),
+                    kind: options.kind,
+                });
+                app.watched_values.insert(watchpoint.id(), watched);
+            }
+            Err(err) => app
+                .notifications
+                .error(format!("Failed to restore watchpoint: {}", err)),
+        }
+    }
+}
+
+/// How the breakpoint was set, as recorded when the app created it or else as
+/// lldb describes it.
+fn breakpoint_spec(app: &App, breakpoint: &SBBreakpoint) -> Option<BreakpointSpec> {
+    app.breakpoint_specs
+        .get(&breakpoint.id())
+        .cloned()
+        .or_else(|| debugger::parse_breakpoint_spec(breakpoint))
+}
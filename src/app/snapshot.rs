@@ -0,0 +1,522 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use lldb::{
+    LanguageType, SBBreakpoint, SBBreakpointLocation, SBFileSpec, SBFrame, SBProcess, SBTarget,
+    SBThread, SBValue,
+};
+
+use crate::app::worker::{Pending, Worker};
+use crate::debugger::{self, BreakpointCommands, BreakpointOptions, StopInfo, WatchKind};
+
+/// State of the stopped process. It is built on the worker once per stop (and
+/// selection change) so the components don't have to query LLDB on every
+/// frame, which would block while the worker runs an expression.
+pub struct Snapshot {
+    pub threads: Vec<ThreadInfo>,
+    pub selected_thread_id: u64,
+    pub frames: Vec<FrameInfo>,
+    pub selected_frame_id: u32,
+    pub frame: SelectedFrame,
+    pub locals: Vec<Variable>,
+    pub statics: Vec<Variable>,
+    pub arguments: Vec<Variable>,
+    pub registers: Vec<Variable>,
+    /// Why the process stopped.
+    pub stop: Option<StopInfo>,
+}
+
+pub struct ThreadInfo {
+    pub thread: SBThread,
+    pub label: String,
+}
+
+pub struct FrameInfo {
+    pub frame_id: u32,
+    pub location: String,
+    pub function_name: String,
+}
+
+/// What the source view shows for the selected frame.
+pub struct SelectedFrame {
+    pub source: Option<SourceLocation>,
+    pub language: LanguageType,
+    pub function_name: Option<String>,
+    pub symbol_name: Option<String>,
+    /// Only loaded for frames without source.
+    pub disassembly: Option<String>,
+}
+
+pub struct SourceLocation {
+    pub path: PathBuf,
+    pub line: u32,
+}
+
+impl Snapshot {
+    /// Returns `None` if the process isn't stopped (anymore). This should
+    /// only be called from the worker thread.
+    pub fn new(process: &SBProcess) -> Option<Self> {
+        if !process.is_stopped() {
+            return None;
+        }
+        let selected_thread = process.selected_thread();
+        let frame = selected_thread.selected_frame();
+
+        let threads = process
+            .threads()
+            .map(|thread| ThreadInfo {
+                label: thread_label(&thread),
+                thread,
+            })
+            .collect();
+        let frames = selected_thread
+            .frames()
+            .map(|frame| FrameInfo {
+                frame_id: frame.frame_id(),
+                location: frame
+                    .line_entry()
+                    .map(|line_entry| {
+                        format!("{}:{}", line_entry.filespec().filename(), line_entry.line())
+                    })
+                    .unwrap_or_default(),
+                function_name: frame
+                    .display_function_name()
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+            .collect();
+
+        Some(Self {
+            threads,
+            selected_thread_id: selected_thread.thread_id(),
+            frames,
+            selected_frame_id: frame.frame_id(),
+            locals: frame.locals().iter().map(Variable::new).collect(),
+            statics: frame.statics().iter().map(Variable::new).collect(),
+            arguments: frame.arguments().iter().map(Variable::new).collect(),
+            registers: frame.registers().iter().map(Variable::new).collect(),
+            frame: SelectedFrame::new(&frame),
+            stop: debugger::stop_info(process),
+        })
+    }
+
+    /// If the selected frame has a parent to step out to.
+    pub fn frame_has_parent(&self) -> bool {
+        self.frames
+            .iter()
+            .any(|frame| frame.frame_id > self.selected_frame_id)
+    }
+}
+
+impl SelectedFrame {
+    fn new(frame: &SBFrame) -> Self {
+        let source = frame.line_entry().map(|line_entry| SourceLocation {
+            path: file_path(&line_entry.filespec()),
+            line: line_entry.line(),
+        });
+        let function = frame.function();
+        let symbol = frame.symbol();
+        let disassembly = source.is_none().then(|| frame.disassemble().to_string());
+        Self {
+            language: frame.compile_unit().language(),
+            function_name: function
+                .is_valid()
+                .then(|| function.display_name().to_string()),
+            symbol_name: symbol.is_valid().then(|| symbol.display_name().to_string()),
+            disassembly,
+            source,
+        }
+    }
+}
+
+fn thread_label(thread: &SBThread) -> String {
+    let mut label = format!(
+        "{} {}",
+        thread.thread_id(),
+        thread.name().unwrap_or_default()
+    );
+    if let Some(queue) = thread.queue() {
+        label.push_str(&format!(" queue={}", queue.name()));
+    }
+    let description = thread.stop_description();
+    if thread.is_stopped() && !description.is_empty() {
+        label.push_str(&format!(" ({})", description));
+    }
+    label
+}
+
+/// A debugger value. Its children are loaded on the worker when they are shown
+/// the first time.
+pub struct Variable {
+    pub value: SBValue,
+    pub name: String,
+    pub type_name: String,
+    pub display_value: String,
+    pub load_address: Option<u64>,
+    pub has_children: bool,
+    children: Pending<Vec<Variable>>,
+}
+
+impl Variable {
+    pub fn new(value: SBValue) -> Self {
+        Self {
+            name: value.name().unwrap_or_default().to_string(),
+            type_name: value.display_type_name().unwrap_or_default().to_string(),
+            display_value: value.value().unwrap_or_default().to_string(),
+            load_address: value.load_address(),
+            has_children: value.children().next().is_some(),
+            children: Pending::NotStarted,
+            value,
+        }
+    }
+
+    pub fn children(&mut self, worker: &Worker) -> &mut Pending<Vec<Variable>> {
+        if let Pending::NotStarted = self.children {
+            let value = self.value.clone();
+            self.children = Pending::Running(worker.run(move |token| {
+                value
+                    .children()
+                    .take_while(|_| !token.is_cancelled())
+                    .map(Variable::new)
+                    .collect()
+            }));
+        }
+        &mut self.children
+    }
+}
+
+/// An expression that is evaluated in the selected frame on every stop.
+pub struct WatchExpression {
+    pub expression: String,
+    pub value: Pending<Result<Variable, String>>,
+}
+
+impl WatchExpression {
+    pub fn new(expression: String) -> Self {
+        Self {
+            expression,
+            value: Pending::NotStarted,
+        }
+    }
+
+    /// Starts the evaluation in the selected frame if the expression wasn't
+    /// evaluated yet.
+    pub fn evaluate(&mut self, process: &SBProcess, worker: &Worker) {
+        if let Pending::NotStarted = self.value {
+            let process = process.clone();
+            let expression = self.expression.clone();
+            self.value = Pending::Running(worker.run(move |_| {
+                let frame = process.selected_thread().selected_frame();
+                debugger::evaluate(&frame, &expression)
+                    .map(|value| Variable {
+                        // Show the expression instead of the name of the result variable.
+                        name: expression,
+                        ..Variable::new(value)
+                    })
+                    .map_err(|err| err.to_string())
+            }));
+        }
+    }
+}
+
+/// Breakpoints and watchpoints of the target. It is rebuilt on the worker
+/// whenever they change.
+pub struct BreakpointIndex {
+    pub breakpoints: Vec<BreakpointInfo>,
+    /// Breakpoint ids and if the location is enabled by the file and line of
+    /// the resolved locations.
+    by_line: HashMap<(PathBuf, u32), (i32, bool)>,
+    pub watchpoints: Vec<WatchpointInfo>,
+    /// Watchpoint ids by watched address.
+    pub watched_addresses: HashMap<u64, i32>,
+}
+
+/// A breakpoint with its locations. Pending breakpoints, e.g. on a function
+/// of a shared library that isn't loaded yet, don't have any.
+pub struct BreakpointInfo {
+    pub breakpoint: SBBreakpoint,
+    pub id: i32,
+    /// How the breakpoint was set, e.g. `line` or `function`.
+    pub kind: &'static str,
+    /// What the breakpoint was set on, as described by lldb.
+    pub spec: String,
+    pub options: BreakpointOptions,
+    /// The message template if the breakpoint is a logpoint.
+    pub log_message: Option<String>,
+    pub commands: Option<BreakpointCommands>,
+    pub hit_count: u32,
+    pub locations: Vec<LocationInfo>,
+}
+
+pub struct LocationInfo {
+    pub location: SBBreakpointLocation,
+    pub id: i32,
+    pub address: Option<u64>,
+    pub function_name: Option<String>,
+    /// The source file and line the location resolved to.
+    pub line_entry: Option<(PathBuf, u32)>,
+    pub resolved: bool,
+    pub enabled: bool,
+    pub hit_count: u32,
+}
+
+pub struct WatchpointInfo {
+    pub id: i32,
+    pub address: u64,
+    pub size: usize,
+    pub hit_count: u32,
+    pub condition: Option<String>,
+}
+
+impl BreakpointIndex {
+    /// This should only be called from the worker thread.
+    /// `hidden` is the breakpoint used internally to run to `main`.
+    /// `logpoints` are the message templates of the logpoints and `commands`
+    /// the commands attached to the breakpoints, by breakpoint id.
+    pub fn new(
+        target: &SBTarget,
+        hidden: Option<i32>,
+        logpoints: &HashMap<i32, String>,
+        commands: &HashMap<i32, BreakpointCommands>,
+    ) -> Self {
+        let breakpoints: Vec<BreakpointInfo> = target
+            .breakpoints()
+            .filter(|breakpoint| Some(breakpoint.id()) != hidden)
+            .map(|breakpoint| {
+                let log_message = logpoints.get(&breakpoint.id()).cloned();
+                let commands = commands.get(&breakpoint.id()).cloned();
+                BreakpointInfo::new(breakpoint, log_message, commands)
+            })
+            .collect();
+        let by_line = breakpoints
+            .iter()
+            .flat_map(|breakpoint| {
+                breakpoint.locations.iter().filter_map(|location| {
+                    let (file, line) = location.line_entry.clone()?;
+                    let enabled = breakpoint.options.enabled && location.enabled;
+                    Some(((file, line), (breakpoint.id, enabled)))
+                })
+            })
+            .collect();
+        let watchpoints: Vec<WatchpointInfo> = target
+            .watchpoints()
+            .map(|watchpoint| WatchpointInfo {
+                id: watchpoint.id(),
+                address: watchpoint.watch_address(),
+                size: watchpoint.watch_size(),
+                hit_count: watchpoint.hit_count(),
+                condition: watchpoint.condition().map(String::from),
+            })
+            .collect();
+        let watched_addresses = watchpoints
+            .iter()
+            .map(|watchpoint| (watchpoint.address, watchpoint.id))
+            .collect();
+        Self {
+            breakpoints,
+            by_line,
+            watchpoints,
+            watched_addresses,
+        }
+    }
+
+    /// Returns the id of the breakpoint at the given line and if it is enabled.
+    pub fn breakpoint_at(&self, file: &Path, line: u32) -> Option<(i32, bool)> {
+        self.by_line.get(&(file.to_path_buf(), line)).copied()
+    }
+
+    pub fn breakpoint(&self, id: i32) -> Option<&BreakpointInfo> {
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| breakpoint.id == id)
+    }
+}
+
+impl BreakpointInfo {
+    pub fn new(
+        breakpoint: SBBreakpoint,
+        log_message: Option<String>,
+        commands: Option<BreakpointCommands>,
+    ) -> Self {
+        let (kind, spec) = debugger::breakpoint_spec(&breakpoint);
+        Self {
+            id: breakpoint.id(),
+            kind,
+            spec,
+            options: debugger::breakpoint_options(&breakpoint),
+            log_message,
+            commands,
+            hit_count: breakpoint.hit_count(),
+            locations: breakpoint
+                .locations()
+                .map(|location| {
+                    let address = location.address();
+                    LocationInfo {
+                        id: location.id(),
+                        address: address.as_ref().map(|_| location.load_address()),
+                        function_name: address.as_ref().and_then(|address| {
+                            let function = address.function();
+                            function
+                                .is_valid()
+                                .then(|| function.display_name().to_string())
+                        }),
+                        line_entry: address
+                            .as_ref()
+                            .and_then(|address| address.line_entry())
+                            .map(|line_entry| {
+                                (file_path(&line_entry.filespec()), line_entry.line())
+                            }),
+                        resolved: location.is_resolved(),
+                        enabled: location.is_enabled(),
+                        hit_count: location.hit_count(),
+                        location,
+                    }
+                })
+                .collect(),
+            breakpoint,
+        }
+    }
+
+    /// A breakpoint is pending until one of its locations is resolved.
+    pub fn is_pending(&self) -> bool {
+        !self.locations.iter().any(|location| location.resolved)
+    }
+}
+
+/// What a watchpoint was set on, lldb only knows the address.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct WatchedValue {
+    /// The expression or address entered in the watchpoint editor.
+    pub name: String,
+    pub type_name: String,
+    pub kind: WatchKind,
+}
+
+/// The watched memory before and after the access that triggered the
+/// watchpoint the process stopped at.
+pub struct WatchpointChange {
+    pub id: i32,
+    /// `None` if the memory couldn't be read before.
+    pub old_value: Option<Vec<u8>>,
+    pub new_value: Vec<u8>,
+}
+
+/// What the worker found out about a stop before the UI shows it.
+#[derive(Default)]
+pub struct StopUpdate {
+    /// The messages of the logpoints the threads stopped at, the process was
+    /// continued right away.
+    pub log_messages: Vec<String>,
+    /// Why the process couldn't be continued after the logpoints.
+    pub continue_error: Option<String>,
+    /// The watchpoint the process stopped at.
+    pub watchpoint_hit: Option<i32>,
+    /// The watched memory by watchpoint id.
+    pub watch_memory: HashMap<i32, Vec<u8>>,
+    /// The watchpoints that are enabled, lldb disables them all once the
+    /// process goes away.
+    pub enabled_watchpoints: Vec<i32>,
+}
+
+impl StopUpdate {
+    /// Formats the messages and continues the process if it stopped at
+    /// logpoints, otherwise reads the watched memory. `logpoints` are the
+    /// message templates by breakpoint id. This should only be called from
+    /// the worker thread.
+    pub fn new(target: &SBTarget, logpoints: &HashMap<i32, String>) -> Self {
+        let process = target.process();
+        // Only continue if every thread that stopped is at a logpoint, another
+        // one might have hit a breakpoint or crashed at the same time.
+        let logpoint_hits: Option<Vec<(&String, SBThread)>> = debugger::stopped_threads(&process)
+            .into_iter()
+            .map(|thread| {
+                let id = debugger::thread_breakpoint_hit(&thread)?;
+                Some((logpoints.get(&id)?, thread))
+            })
+            .collect();
+        if let Some(hits) = logpoint_hits.filter(|hits| !hits.is_empty()) {
+            let log_messages = hits
+                .iter()
+                .map(|(template, thread)| {
+                    debugger::format_log_message(&thread.selected_frame(), template)
+                })
+                .collect();
+            return Self {
+                log_messages,
+                continue_error: process
+                    .continue_execution()
+                    .err()
+                    .map(|err| err.to_string()),
+                ..Default::default()
+            };
+        }
+        let watch_memory = target
+            .watchpoints()
+            .filter_map(|watchpoint| {
+                let address = watchpoint.watch_address();
+                match debugger::read_memory(&process, address, watchpoint.watch_size()) {
+                    Ok(value) => Some((watchpoint.id(), value)),
+                    Err(err) => {
+                        tracing::debug!("Failed to read watched memory: {}", err);
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self {
+            log_messages: Vec::new(),
+            continue_error: None,
+            watchpoint_hit: debugger::watchpoint_hit(&process),
+            watch_memory,
+            enabled_watchpoints: target
+                .watchpoints()
+                .filter(|watchpoint| watchpoint.is_enabled())
+                .map(|watchpoint| watchpoint.id())
+                .collect(),
+        }
+    }
+}
+
+/// The source files of a module, as listed by its compile units.
+pub struct ModuleSources {
+    pub name: String,
+    pub compile_units: Vec<CompileUnitSources>,
+}
+
+pub struct CompileUnitSources {
+    pub path: PathBuf,
+    /// The source and header files the compile unit was built from.
+    pub files: Vec<PathBuf>,
+}
+
+impl ModuleSources {
+    /// Lists the sources of all modules with debug info. This reads the debug
+    /// info of every module, it should only be called from the worker thread.
+    pub fn load(target: &SBTarget) -> Vec<Self> {
+        target
+            .modules()
+            .map(|module| ModuleSources {
+                name: module.filespec().filename().to_string(),
+                compile_units: module
+                    .compile_units()
+                    .map(|compile_unit| CompileUnitSources {
+                        path: file_path(&compile_unit.filespec()),
+                        files: compile_unit
+                            .support_files()
+                            .filter(|file| !file.filename().is_empty())
+                            .map(|file| file_path(&file))
+                            .collect::<BTreeSet<PathBuf>>()
+                            .into_iter()
+                            .collect(),
+                    })
+                    .filter(|compile_unit| !compile_unit.files.is_empty())
+                    .collect(),
+            })
+            .filter(|module| !module.compile_units.is_empty())
+            .collect()
+    }
+}
+
+pub fn file_path(filespec: &SBFileSpec) -> PathBuf {
+    [filespec.directory(), filespec.filename()].iter().collect()
+}
@@ -0,0 +1,370 @@
+use std::path::Path;
+
+use egui::text::{LayoutJob, TextFormat};
+use egui::{Color32, Context, TextStyle};
+use egui_extras::syntax_highlighting::{self, CodeTheme};
+use lldb::LanguageType;
+
+pub const PLAIN_TEXT: &str = "Plain Text";
+/// Not supported by syntect, highlighted by `highlight_assembly`.
+pub const ASSEMBLY: &str = "Assembly";
+
+/// The languages the highlighting can be overridden with in the source view.
+pub const LANGUAGES: &[&str] = &[
+    PLAIN_TEXT,
+    ASSEMBLY,
+    "C",
+    "C++",
+    "D",
+    "Go",
+    "Haskell",
+    "Java",
+    "Lisp",
+    "Objective-C",
+    "Objective-C++",
+    "OCaml",
+    "Pascal",
+    "Python",
+    "Rust",
+];
+
+/// Highlights a line of source code in the given language. Languages syntect
+/// doesn't know are rendered as plain text.
+pub fn highlight(ctx: &Context, theme: &CodeTheme, code: &str, language: &str) -> LayoutJob {
+    if language == ASSEMBLY {
+        highlight_assembly(ctx, code)
+    } else {
+        syntax_highlighting::highlight(ctx, theme, code, language)
+    }
+}
+
+/// Detects the language of a source file, falling back from the language of
+/// the compile unit to the file extension to plain text.
+pub fn detect_language(language: LanguageType, path: &Path) -> String {
+    compile_unit_language(language)
+        .map(String::from)
+        .or_else(|| extension_language(path))
+        .unwrap_or_else(|| PLAIN_TEXT.to_string())
+}
+
+// https://github.com/trishume/syntect
+// Supported file types:
+// - Plain Text (.txt)
+// - ASP (.asa)
+// - HTML (ASP) (.asp)
+// - ActionScript (.as)
+// - AppleScript (.applescript, .script editor)
+// - Batch File (.bat, .cmd)
+// - NAnt Build File (.build)
+// - C# (.cs, .csx)
+// - C++ (.cpp, .cc, .cp, .cxx, .c++, .C, .h, .hh, .hpp, .hxx, .h++, .inl, .ipp)
+// - C (.c, .h)
+// - CSS (.css, .css.erb, .css.liquid)
+// - Clojure (.clj)
+// - D (.d, .di)
+// - Diff (.diff, .patch)
+// - Erlang (.erl, .hrl, .Emakefile, .emakefile)
+// - HTML (Erlang) (.yaws)
+// - Go (.go)
+// - Graphviz (DOT) (.dot, .DOT, .gv)
+// - Groovy (.groovy, .gvy, .gradle)
+// - HTML (.html, .htm, .shtml, .xhtml, .inc, .tmpl, .tpl)
+// - Haskell (.hs)
+// - Literate Haskell (.lhs)
+// - Java Server Page (JSP) (.jsp)
+// - Java (.java, .bsh)
+// - JavaDoc (.)
+// - Java Properties (.properties)
+// - JSON (.json, .sublime-settings, .sublime-menu, .sublime-keymap, .sublime-mousemap, .sublime-theme, .sublime-build, .sublime-project, .sublime-completions, .sublime-commands, .sublime-macro, .sublime-color-scheme)
+// - JavaScript (.js, .htc)
+// - Regular Expressions (Javascript) (.)
+// - BibTeX (.bib)
+// - LaTeX Log (.)
+// - LaTeX (.tex, .ltx)
+// - TeX (.sty, .cls)
+// - Lisp (.lisp, .cl, .clisp, .l, .mud, .el, .scm, .ss, .lsp, .fasl)
+// - Lua (.lua)
+// - Make Output (.)
+// - Makefile (.make, .GNUmakefile, .makefile, .Makefile, .OCamlMakefile, .mak, .mk)
+// - Markdown (.md, .mdown, .markdown, .markdn)
+// - MultiMarkdown (.)
+// - MATLAB (.matlab)
+// - OCaml (.ml, .mli)
+// - OCamllex (.mll)
+// - OCamlyacc (.mly)
+// - camlp4 (.)
+// - Objective-C++ (.mm, .M, .h)
+// - Objective-C (.m, .h)
+// - PHP Source (.)
+// - PHP (.php, .php3, .php4, .php5, .php7, .phps, .phpt, .phtml)
+// - Pascal (.pas, .p, .dpr)
+// - Perl (.pl, .pm, .pod, .t, .PL)
+// - Python (.py, .py3, .pyw, .pyi, .pyx, .pyx.in, .pxd, .pxd.in, .pxi, .pxi.in, .rpy, .cpy, .SConstruct, .Sconstruct, .sconstruct, .SConscript, .gyp, .gypi, .Snakefile, .wscript)
+// - Regular Expressions (Python) (.)
+// - R Console (.)
+// - R (.R, .r, .s, .S, .Rprofile)
+// - Rd (R Documentation) (.rd)
+// - HTML (Rails) (.rails, .rhtml, .erb, .html.erb)
+// - JavaScript (Rails) (.js.erb)
+// - Ruby Haml (.haml, .sass)
+// - Ruby on Rails (.rxml, .builder)
+// - SQL (Rails) (.erbsql, .sql.erb)
+// - Regular Expression (.re)
+// - reStructuredText (.rst, .rest)
+// - Ruby (.rb, .Appfile, .Appraisals, .Berksfile, .Brewfile, .capfile, .cgi, .Cheffile, .config.ru, .Deliverfile, .Fastfile, .fcgi, .Gemfile, .gemspec, .Guardfile, .irbrc, .jbuilder, .podspec, .prawn, .rabl, .rake, .Rakefile, .Rantfile, .rbx, .rjs, .ruby.rail, .Scanfile, .simplecov, .Snapfile, .thor, .Thorfile, .Vagrantfile)
+// - Cargo Build Results (.)
+// - Rust (.rs)
+// - SQL (.sql, .ddl, .dml)
+// - Scala (.scala, .sbt)
+// - Bourne Again Shell (bash) (.sh, .bash, .zsh, .fish, ..bash_aliases, ..bash_completions, ..bash_functions, ..bash_login, ..bash_logout, ..bash_profile, ..bash_variables, ..bashrc, ..profile, ..textmate_init)
+// - Shell-Unix-Generic (.)
+// - commands-builtin-shell-bash (.)
+// - HTML (Tcl) (.adp)
+// - Tcl (.tcl)
+// - Textile (.textile)
+// - XML (.xml, .xsd, .xslt, .tld, .dtml, .rss, .opml, .svg)
+// - YAML (.yaml, .yml, .sublime-syntax)
+fn compile_unit_language(language: LanguageType) -> Option<&'static str> {
+    match language {
+        LanguageType::C89 | LanguageType::C | LanguageType::C99 | LanguageType::C11 => Some("C"),
+        LanguageType::C_plus_plus
+        | LanguageType::C_plus_plus_03
+        | LanguageType::C_plus_plus_11
+        | LanguageType::C_plus_plus_14 => Some("C++"),
+        LanguageType::Java => Some("Java"),
+        LanguageType::ObjC => Some("Objective-C"),
+        LanguageType::ObjC_plus_plus => Some("Objective-C++"),
+        LanguageType::D => Some("D"),
+        LanguageType::Python => Some("Python"),
+        LanguageType::Go => Some("Go"),
+        LanguageType::Haskell => Some("Haskell"),
+        LanguageType::OCaml => Some("OCaml"),
+        LanguageType::Rust => Some("Rust"),
+        LanguageType::Pascal83 => Some("Pascal"),
+        LanguageType::MipsAssembler => Some(ASSEMBLY),
+        // Unknown or not supported by syntect, e.g. Fortran, Ada or Swift.
+        _ => None,
+    }
+}
+
+fn extension_language(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?;
+    match extension {
+        // syntect would pick R for these.
+        "s" | "S" | "asm" => Some(ASSEMBLY.to_string()),
+        // syntect looks the language up by extension as well.
+        _ => Some(extension.to_string()),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AsmToken {
+    Text,
+    Comment,
+    Label,
+    Directive,
+    Mnemonic,
+    Register,
+    Number,
+    String,
+}
+
+/// A simple highlighter for GNU and Intel style assembly of the common
+/// architectures.
+fn highlight_assembly(ctx: &Context, code: &str) -> LayoutJob {
+    let style = ctx.style();
+    let font_id = TextStyle::Monospace.resolve(&style);
+    let dark_mode = style.visuals.dark_mode;
+
+    let mut job = LayoutJob::default();
+    for (text, token) in asm_tokens(code) {
+        let color = match (token, dark_mode) {
+            (AsmToken::Text, _) => style.visuals.text_color(),
+            (AsmToken::Comment, _) => Color32::GRAY,
+            (AsmToken::Label, true) => Color32::from_rgb(255, 200, 100),
+            (AsmToken::Label, false) => Color32::from_rgb(170, 110, 0),
+            (AsmToken::Directive, true) => Color32::from_rgb(178, 108, 210),
+            (AsmToken::Directive, false) => Color32::from_rgb(130, 50, 170),
+            (AsmToken::Mnemonic, true) => Color32::from_rgb(255, 100, 100),
+            (AsmToken::Mnemonic, false) => Color32::from_rgb(200, 40, 40),
+            (AsmToken::Register, true) => Color32::from_rgb(87, 165, 171),
+            (AsmToken::Register, false) => Color32::from_rgb(20, 110, 120),
+            (AsmToken::Number, true) => Color32::from_rgb(109, 147, 226),
+            (AsmToken::Number, false) => Color32::from_rgb(40, 80, 180),
+            (AsmToken::String, true) => Color32::from_rgb(150, 200, 110),
+            (AsmToken::String, false) => Color32::from_rgb(50, 130, 30),
+        };
+        job.append(text, 0.0, TextFormat::simple(font_id.clone(), color));
+    }
+    job
+}
+
+fn asm_tokens(line: &str) -> Vec<(&str, AsmToken)> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+    // The first word of a statement is the mnemonic, a directive or a label.
+    let mut first_word = true;
+    while let Some(c) = rest.chars().next() {
+        let at_start = line[..line.len() - rest.len()].trim().is_empty();
+        let (len, token) = if rest.starts_with("//") || c == ';' {
+            (rest.len(), AsmToken::Comment)
+        } else if at_start && c == '#' && rest[1..].starts_with(char::is_alphabetic) {
+            // C preprocessor directives in .S files.
+            (rest.len(), AsmToken::Directive)
+        } else if at_start && (c == '#' || c == '@') {
+            (rest.len(), AsmToken::Comment)
+        } else if c == '"' {
+            let len = rest[1..].find('"').map(|i| i + 2).unwrap_or(rest.len());
+            (len, AsmToken::String)
+        } else if c.is_alphanumeric() || "_.$%#@".contains(c) {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || "_.$%#@".contains(c)))
+                .unwrap_or(rest.len());
+            let word = &rest[..len];
+            if word.starts_with('%') || is_register(word) {
+                (len, AsmToken::Register)
+            } else if word
+                .trim_start_matches(['$', '#'])
+                .starts_with(|c: char| c.is_ascii_digit())
+            {
+                (len, AsmToken::Number)
+            } else if first_word && rest[len..].starts_with(':') {
+                (len + 1, AsmToken::Label)
+            } else if first_word {
+                first_word = false;
+                if word.starts_with('.') {
+                    (len, AsmToken::Directive)
+                } else {
+                    (len, AsmToken::Mnemonic)
+                }
+            } else {
+                (len, AsmToken::Text)
+            }
+        } else {
+            (c.len_utf8(), AsmToken::Text)
+        };
+        tokens.push((&rest[..len], token));
+        rest = &rest[len..];
+    }
+    tokens
+}
+
+// Registers of x86 (Intel syntax, AT&T registers start with %) and ARM.
+fn is_register(word: &str) -> bool {
+    let word = word.to_lowercase();
+    const NAMES: &[&str] = &[
+        "rax", "rbx", "rcx", "rdx", "rsi", "rdi", "rbp", "rsp", "rip", "eax", "ebx", "ecx", "edx",
+        "esi", "edi", "ebp", "esp", "eip", "ax", "bx", "cx", "dx", "al", "bl", "cl", "dl", "sp",
+        "lr", "pc", "fp", "xzr", "wzr",
+    ];
+    if NAMES.contains(&word.as_str()) {
+        return true;
+    }
+    // Numbered registers like r8, x0, w1, q2, d3, s4, v5, xmm0 or ymm1.
+    let number = word
+        .strip_prefix("xmm")
+        .or_else(|| word.strip_prefix("ymm"))
+        .or_else(|| word.strip_prefix(['r', 'x', 'w', 'q', 'd', 's', 'v']));
+    number.is_some_and(|n| !n.is_empty() && n.len() <= 2 && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_the_compile_unit_language_first() {
+        assert_eq!(
+            detect_language(LanguageType::C99, Path::new("main.rs")),
+            "C"
+        );
+        assert_eq!(
+            detect_language(LanguageType::MipsAssembler, Path::new("start")),
+            ASSEMBLY
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_extension() {
+        assert_eq!(
+            detect_language(LanguageType::Unknown, Path::new("lib.rs")),
+            "rs"
+        );
+        assert_eq!(
+            detect_language(LanguageType::Unknown, Path::new("start.S")),
+            ASSEMBLY
+        );
+        // syntect renders extensions it doesn't know as plain text.
+        assert_eq!(
+            detect_language(LanguageType::Unknown, Path::new("data.xyz")),
+            "xyz"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_plain_text() {
+        assert_eq!(
+            detect_language(LanguageType::Unknown, Path::new("Makefile")),
+            PLAIN_TEXT
+        );
+    }
+
+    // The tokens of the line without the whitespace between them.
+    fn tokens(line: &str) -> Vec<(&str, AsmToken)> {
+        asm_tokens(line)
+            .into_iter()
+            .filter(|(text, _)| !text.trim().is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn highlights_intel_assembly() {
+        assert_eq!(
+            tokens("  mov rax, 42 ; answer"),
+            [
+                ("mov", AsmToken::Mnemonic),
+                ("rax", AsmToken::Register),
+                (",", AsmToken::Text),
+                ("42", AsmToken::Number),
+                ("; answer", AsmToken::Comment),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlights_att_assembly() {
+        assert_eq!(
+            tokens("loop: addq $0x10, %rsp"),
+            [
+                ("loop:", AsmToken::Label),
+                ("addq", AsmToken::Mnemonic),
+                ("$0x10", AsmToken::Number),
+                (",", AsmToken::Text),
+                ("%rsp", AsmToken::Register),
+            ]
+        );
+        assert_eq!(tokens("# comment"), [("# comment", AsmToken::Comment)]);
+        assert_eq!(
+            tokens(".section .text"),
+            [(".section", AsmToken::Directive), (".text", AsmToken::Text)]
+        );
+    }
+
+    #[test]
+    fn highlights_arm_assembly() {
+        assert_eq!(
+            tokens("ldr x0, [sp, #8] // load"),
+            [
+                ("ldr", AsmToken::Mnemonic),
+                ("x0", AsmToken::Register),
+                (",", AsmToken::Text),
+                ("[", AsmToken::Text),
+                ("sp", AsmToken::Register),
+                (",", AsmToken::Text),
+                ("#8", AsmToken::Number),
+                ("]", AsmToken::Text),
+                ("// load", AsmToken::Comment),
+            ]
+        );
+    }
+}
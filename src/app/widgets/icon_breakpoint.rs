@@ -1,12 +1,20 @@
 use egui::{Color32, Response, Sense, Stroke, Ui, Vec2, Widget};
 
+/// The breakpoint marker in the gutter of the source view.
+#[derive(Clone, Copy, PartialEq)]
+pub enum BreakpointState {
+    None,
+    Enabled,
+    Disabled,
+}
+
 pub struct IconBreakpoint {
-    enabled: bool,
+    state: BreakpointState,
 }
 
 impl IconBreakpoint {
-    pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+    pub fn new(state: BreakpointState) -> Self {
+        Self { state }
     }
 }
 
@@ -16,16 +24,28 @@ impl Widget for IconBreakpoint {
         let padding = row_height / 2.;
         let (rect, resp) = ui.allocate_at_least(Vec2::new(row_height, row_height), Sense::click());
         let radius = (row_height - padding) / 2.;
-        if self.enabled {
-            ui.painter()
-                .circle_filled(rect.center(), radius, ui.visuals().error_fg_color);
-        } else if resp.hovered() {
-            ui.painter().circle(
-                rect.center(),
-                radius,
-                Color32::TRANSPARENT,
-                Stroke::new(1., ui.visuals().error_fg_color),
-            );
+        match self.state {
+            BreakpointState::Enabled => {
+                ui.painter()
+                    .circle_filled(rect.center(), radius, ui.visuals().error_fg_color);
+            }
+            BreakpointState::Disabled => {
+                ui.painter().circle(
+                    rect.center(),
+                    radius,
+                    ui.visuals().widgets.inactive.bg_fill,
+                    Stroke::new(1., ui.visuals().weak_text_color()),
+                );
+            }
+            BreakpointState::None if resp.hovered() => {
+                ui.painter().circle(
+                    rect.center(),
+                    radius,
+                    Color32::TRANSPARENT,
+                    Stroke::new(1., ui.visuals().error_fg_color),
+                );
+            }
+            BreakpointState::None => {}
         }
         resp
     }
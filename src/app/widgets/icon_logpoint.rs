@@ -0,0 +1,40 @@
+use egui::{epaint::PathShape, Pos2, Response, Sense, Stroke, Ui, Vec2, Widget};
+
+/// The logpoint marker in the gutter of the source view, a diamond instead of
+/// the circle of `IconBreakpoint`.
+pub struct IconLogpoint {
+    enabled: bool,
+}
+
+impl IconLogpoint {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+impl Widget for IconLogpoint {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let row_height = ui.spacing().interact_size.y;
+        let padding = row_height / 2.;
+        let (rect, resp) = ui.allocate_at_least(Vec2::new(row_height, row_height), Sense::click());
+        let radius = (row_height - padding) / 2.;
+        let center = rect.center();
+        let points = vec![
+            Pos2::new(center.x, center.y - radius),
+            Pos2::new(center.x + radius, center.y),
+            Pos2::new(center.x, center.y + radius),
+            Pos2::new(center.x - radius, center.y),
+        ];
+        let shape = if self.enabled {
+            PathShape::convex_polygon(points, ui.visuals().warn_fg_color, Stroke::NONE)
+        } else {
+            PathShape::convex_polygon(
+                points,
+                ui.visuals().widgets.inactive.bg_fill,
+                Stroke::new(1., ui.visuals().weak_text_color()),
+            )
+        };
+        ui.painter().add(shape);
+        resp
+    }
+}
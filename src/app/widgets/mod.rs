@@ -2,10 +2,14 @@ pub mod ansi_string;
 pub mod icon_arrow;
 pub mod icon_breakpoint;
 pub mod icon_button;
+pub mod icon_logpoint;
+pub mod pending;
 pub mod variable_list;
 
 pub use ansi_string::AnsiString;
 pub use icon_arrow::IconArrow;
-pub use icon_breakpoint::IconBreakpoint;
+pub use icon_breakpoint::{BreakpointState, IconBreakpoint};
 pub use icon_button::IconButton;
-pub use variable_list::VariableList;
+pub use icon_logpoint::IconLogpoint;
+pub use pending::show_pending;
+pub use variable_list::{VariableList, WatchRequest};
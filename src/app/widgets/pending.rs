@@ -0,0 +1,33 @@
+use egui::Ui;
+use lldb::SBProcess;
+
+use crate::app::worker::Pending;
+use crate::debugger;
+
+/// Shows a spinner with a cancel button while the value is loading and a retry
+/// button if loading was cancelled. Returns the value once it is loaded.
+pub fn show_pending<'a, T>(
+    ui: &mut Ui,
+    pending: &'a mut Pending<T>,
+    process: &SBProcess,
+) -> Option<&'a mut T> {
+    if pending.poll().is_some() {
+        return pending.poll();
+    }
+    ui.horizontal(|ui| {
+        if pending.is_running() {
+            ui.spinner();
+            if ui.button("Cancel").clicked() {
+                // Stops a running expression, e.g. of a data formatter.
+                debugger::interrupt(process);
+                pending.cancel();
+            }
+        } else if let Pending::Cancelled = pending {
+            ui.label("cancelled");
+            if ui.button("Retry").clicked() {
+                pending.reset();
+            }
+        }
+    });
+    None
+}
@@ -1,79 +1,110 @@
 use std::collections::HashMap;
 
 use egui::{CollapsingHeader, CursorIcon, Label, Response, Sense, Ui, Widget};
-use lldb::{SBTarget, SBValue, SBWatchpoint};
+use lldb::SBTarget;
+
+use crate::app::snapshot::Variable;
+use crate::app::widgets::show_pending;
+use crate::app::worker::Worker;
+
+/// A variable that was clicked to watch it.
+pub struct WatchRequest {
+    pub name: String,
+    pub type_name: String,
+    pub address: u64,
+    pub size: usize,
+}
 
 /// `VariableList` renders a nested list of debugger values.
 pub struct VariableList<'a> {
-    values: Box<dyn Iterator<Item = SBValue> + 'a>,
+    variables: &'a mut [Variable],
     target: &'a SBTarget,
+    /// Watchpoint ids by watched address.
+    watchpoints: &'a HashMap<u64, i32>,
+    worker: &'a Worker,
+    watch: &'a mut Option<WatchRequest>,
 }
 
 impl<'a> VariableList<'a> {
-    pub fn new(values: impl Iterator<Item = SBValue> + 'a, target: &'a SBTarget) -> Self {
+    /// `watch` is set when a variable was clicked to watch it.
+    pub fn new(
+        variables: &'a mut [Variable],
+        target: &'a SBTarget,
+        watchpoints: &'a HashMap<u64, i32>,
+        worker: &'a Worker,
+        watch: &'a mut Option<WatchRequest>,
+    ) -> Self {
         Self {
-            values: Box::new(values),
+            variables,
             target,
+            watchpoints,
+            worker,
+            watch,
         }
     }
 }
 
 impl<'a> Widget for VariableList<'a> {
     fn ui(self, ui: &mut Ui) -> Response {
-        let watchpoints: HashMap<u64, SBWatchpoint> = self
-            .target
-            .watchpoints()
-            .map(|wp| (wp.watch_address(), wp))
-            .collect();
-
         egui::Grid::new(ui.next_auto_id())
             .num_columns(3)
             .striped(true)
             .show(ui, |ui| {
-                for v in self.values {
-                    if v.children().count() > 0 {
-                        CollapsingHeader::new(v.name().unwrap_or("<noname>"))
+                for v in self.variables.iter_mut() {
+                    if v.has_children {
+                        let name = if v.name.is_empty() {
+                            "<noname>"
+                        } else {
+                            &v.name
+                        };
+                        CollapsingHeader::new(name)
                             .id_source(ui.next_auto_id())
                             .show(ui, |ui| {
-                                ui.add(VariableList::new(v.children(), self.target));
+                                let process = self.target.process();
+                                if let Some(children) =
+                                    show_pending(ui, v.children(self.worker), &process)
+                                {
+                                    ui.add(VariableList::new(
+                                        children,
+                                        self.target,
+                                        self.watchpoints,
+                                        self.worker,
+                                        self.watch,
+                                    ));
+                                }
                             });
                     } else {
-                        if let Some(load_address) = v.load_address() {
-                            if let Some(wp) = watchpoints.get(&load_address) {
+                        if let Some(load_address) = v.load_address {
+                            if let Some(wp_id) = self.watchpoints.get(&load_address) {
                                 if ui
-                                    .add(
-                                        Label::new(v.name().unwrap_or_default())
-                                            .sense(Sense::click()),
-                                    )
+                                    .add(Label::new(&v.name).sense(Sense::click()))
                                     .on_hover_cursor(CursorIcon::Default)
                                     .on_hover_text_at_pointer(format!(
                                         "unwatch {:#x}",
-                                        wp.watch_address()
+                                        load_address
                                     ))
                                     .clicked()
                                 {
-                                    self.target.delete_watchpoint(wp.id());
+                                    self.target.delete_watchpoint(*wp_id);
                                 }
                             } else if ui
-                                .add(Label::new(v.name().unwrap_or_default()).sense(Sense::click()))
+                                .add(Label::new(&v.name).sense(Sense::click()))
                                 .on_hover_cursor(CursorIcon::Default)
                                 .on_hover_text_at_pointer(format!("watch {:#x}", load_address))
                                 .clicked()
                             {
-                                match v.watch(true, false, true) {
-                                    Ok(wp) => {
-                                        tracing::debug!("Watchpoint created: {:?}", wp);
-                                    }
-                                    Err(err) => {
-                                        tracing::error!("Failed to create watchpoint: {}", err)
-                                    }
-                                }
+                                *self.watch = Some(WatchRequest {
+                                    name: v.name.clone(),
+                                    type_name: v.type_name.clone(),
+                                    address: load_address,
+                                    size: v.value.byte_size(),
+                                });
                             }
                         } else {
-                            ui.label(v.name().unwrap_or_default());
+                            ui.label(&v.name);
                         }
-                        ui.label(v.display_type_name().unwrap_or_default());
-                        ui.label(v.value().unwrap_or_default());
+                        ui.label(&v.type_name);
+                        ui.label(&v.display_value);
                     }
                     ui.end_row();
                 }
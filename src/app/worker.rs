@@ -0,0 +1,153 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use egui::Context;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Runs slow LLDB calls (evaluating expressions, loading the children of huge
+/// values, disassembling) on a background thread so the UI never waits for
+/// the debuggee.
+pub struct Worker {
+    sender: Option<Sender<Job>>,
+    egui_ctx: Context,
+}
+
+impl Worker {
+    pub fn spawn(egui_ctx: Context) -> Self {
+        let (sender, receiver) = channel::<Job>();
+        thread::spawn(move || {
+            for job in receiver {
+                job();
+            }
+        });
+        Self {
+            sender: Some(sender),
+            egui_ctx,
+        }
+    }
+
+    /// Queues the job and returns a handle to its result. The UI is repainted
+    /// once the job finished.
+    pub fn run<T: Send + 'static>(
+        &self,
+        job: impl FnOnce(&CancelToken) -> T + Send + 'static,
+    ) -> Task<T> {
+        let task = Task {
+            result: Arc::new(Mutex::new(None)),
+            token: CancelToken(Arc::new(AtomicBool::new(false))),
+        };
+        let result = task.result.clone();
+        let token = task.token.clone();
+        let egui_ctx = self.egui_ctx.clone();
+        let job = Box::new(move || {
+            // Skip the jobs that were cancelled while they were queued.
+            if token.is_cancelled() {
+                return;
+            }
+            let value = job(&token);
+            if !token.is_cancelled() {
+                *result.lock().unwrap() = Some(value);
+                egui_ctx.request_repaint();
+            }
+        });
+        if let Some(sender) = &self.sender {
+            if sender.send(job).is_err() {
+                tracing::error!("LLDB worker thread is gone");
+            }
+        }
+        task
+    }
+
+    /// Stops the thread once the queued jobs are done. It isn't joined, a
+    /// running expression could keep it busy for a long time.
+    pub fn shutdown(&mut self) {
+        self.sender = None;
+    }
+}
+
+/// Lets a job check if it was cancelled and can stop early.
+#[derive(Clone)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Handle to the result of a job queued on the `Worker`.
+pub struct Task<T> {
+    result: Arc<Mutex<Option<T>>>,
+    token: CancelToken,
+}
+
+impl<T> Task<T> {
+    /// Takes the result if the job finished.
+    fn take(&self) -> Option<T> {
+        self.result.lock().unwrap().take()
+    }
+
+    fn cancel(&self) {
+        self.token.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// A value that is loaded on the `Worker`.
+pub enum Pending<T> {
+    NotStarted,
+    Running(Task<T>),
+    Done(T),
+    Cancelled,
+}
+
+impl<T> Default for Pending<T> {
+    fn default() -> Self {
+        Pending::NotStarted
+    }
+}
+
+impl<T> Pending<T> {
+    /// Returns the value if it was loaded.
+    pub fn poll(&mut self) -> Option<&mut T> {
+        if let Pending::Running(task) = self {
+            if let Some(value) = task.take() {
+                *self = Pending::Done(value);
+            }
+        }
+        match self {
+            Pending::Done(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Takes the value if it was loaded, it has to be loaded again afterwards.
+    pub fn take(&mut self) -> Option<T> {
+        self.poll()?;
+        match std::mem::take(self) {
+            Pending::Done(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self, Pending::Running(_))
+    }
+
+    pub fn cancel(&mut self) {
+        if let Pending::Running(task) = self {
+            task.cancel();
+        }
+        *self = Pending::Cancelled;
+    }
+
+    /// Cancels a running job and forgets the value so it is loaded again.
+    pub fn reset(&mut self) {
+        if let Pending::Running(task) = self {
+            task.cancel();
+        }
+        *self = Pending::NotStarted;
+    }
+}
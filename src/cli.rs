@@ -1,9 +1,15 @@
 use clap::{ArgGroup, Parser};
 
+use crate::debugger::{self, LaunchOptions, StartupCommands};
+
+/// The options of the launched process can't be combined with a target that
+/// isn't launched.
+const LAUNCH_CONFLICTS: [&str; 2] = ["connect", "core"];
+
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 #[clap(group(
-    ArgGroup::new("target").required(true).args(&["executable", "attach_pid", "attach_name"]),
+    ArgGroup::new("target").args(&["executable", "attach_pid", "attach_name"]),
 ))]
 pub struct Cli {
     pub executable: Option<String>,
@@ -17,7 +23,199 @@ pub struct Cli {
     #[arg(short = 'n', long)]
     pub attach_name: Option<String>,
 
+    /// Tells the debugger to wait for a process with the given name to launch
+    /// before attaching.
+    #[arg(short = 'w', long, requires = "attach_name", conflicts_with = "batch")]
+    pub wait_for: bool,
+
+    /// Connect to a gdb-server or lldb-server stub at the given address instead
+    /// of launching the executable.
+    #[arg(
+        long,
+        value_name = "HOST:PORT",
+        conflicts_with_all = ["attach_pid", "attach_name", "core"]
+    )]
+    pub connect: Option<String>,
+
+    /// Select a remote platform like `remote-linux` to launch and attach
+    /// through, requires `--platform-url`.
+    #[arg(long, value_name = "NAME", requires = "platform_url")]
+    pub platform: Option<String>,
+
+    /// The address of the `lldb-server platform` to connect the remote
+    /// platform to, e.g. `connect://localhost:1234`.
+    #[arg(long, value_name = "URL", requires = "platform")]
+    pub platform_url: Option<String>,
+
+    /// Tells the debugger to use the file as a core file.
+    #[arg(short = 'c', long, requires = "executable")]
+    pub core: Option<String>,
+
+    /// Set the working directory of the launched process.
+    #[arg(long, value_name = "DIR", requires = "executable", conflicts_with_all = LAUNCH_CONFLICTS)]
+    pub working_dir: Option<String>,
+
+    /// Set an environment variable of the launched process.
+    #[arg(
+        long,
+        value_name = "KEY=VALUE",
+        requires = "executable",
+        conflicts_with_all = LAUNCH_CONFLICTS,
+        value_parser = debugger::parse_env_entry
+    )]
+    pub env: Vec<String>,
+
+    /// Remove an inherited environment variable from the launched process.
+    #[arg(long, value_name = "KEY", requires = "executable", conflicts_with_all = LAUNCH_CONFLICTS)]
+    pub unset_env: Vec<String>,
+
+    /// Redirect stdin of the launched process from the given file.
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "executable",
+        conflicts_with_all = LAUNCH_CONFLICTS
+    )]
+    pub stdin: Option<String>,
+
+    /// Redirect stdout of the launched process to the given file.
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "executable",
+        conflicts_with_all = LAUNCH_CONFLICTS
+    )]
+    pub stdout: Option<String>,
+
+    /// Redirect stderr of the launched process to the given file.
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "executable",
+        conflicts_with_all = LAUNCH_CONFLICTS
+    )]
+    pub stderr: Option<String>,
+
+    /// Disable address space layout randomization of the launched process.
+    #[arg(long, requires = "executable", conflicts_with_all = LAUNCH_CONFLICTS)]
+    pub disable_aslr: bool,
+
+    /// Run the launched process to `main` instead of stopping at its entry point.
+    #[arg(long, requires = "executable", conflicts_with_all = LAUNCH_CONFLICTS)]
+    pub run_to_main: bool,
+
+    /// Look for source files under the path OLD in NEW, e.g. when the
+    /// executable was built on another machine.
+    #[arg(long, value_name = "OLD=NEW", value_parser = parse_source_map)]
+    pub source_map: Vec<(String, String)>,
+
+    /// Tells the debugger to execute this one-line lldb command after any file
+    /// provided on the command line has been loaded.
+    #[arg(short = 'o', long, value_name = "COMMAND")]
+    pub one_line: Vec<String>,
+
+    /// Tells the debugger to execute this one-line lldb command before any file
+    /// provided on the command line has been loaded.
+    #[arg(short = 'O', long, value_name = "COMMAND")]
+    pub one_line_before_file: Vec<String>,
+
+    /// Tells the debugger to read in and execute the lldb commands in the given
+    /// file, after any file has been loaded.
+    #[arg(short = 's', long, value_name = "FILE")]
+    pub source: Vec<String>,
+
+    /// Tells the debugger to read in and execute the lldb commands in the given
+    /// file, before any file has been loaded.
+    #[arg(short = 'S', long, value_name = "FILE")]
+    pub source_before_file: Vec<String>,
+
+    /// When in batch mode, tells the debugger to execute this one-line lldb
+    /// command if the target crashes.
+    #[arg(short = 'k', long, value_name = "COMMAND")]
+    pub one_line_on_crash: Vec<String>,
+
+    /// Tells the debugger to run the commands from -s, -S, -o & -O, and then
+    /// quit. If the process crashed the UI is opened at the place of the crash.
+    #[arg(short = 'b', long, requires = "target")]
+    pub batch: bool,
+
     /// Do not automatically parse any '.lldbinit' files.
     #[arg(short = 'x', long)]
     pub no_lldbinit: bool,
 }
+
+impl Cli {
+    pub fn launch_options(&self) -> LaunchOptions {
+        LaunchOptions {
+            args: self.args.clone().unwrap_or_default(),
+            working_dir: self.working_dir.clone(),
+            env: self.env.clone(),
+            unset_env: self.unset_env.clone(),
+            stdin: self.stdin.clone(),
+            stdout: self.stdout.clone(),
+            stderr: self.stderr.clone(),
+            disable_aslr: self.disable_aslr,
+            run_to_main: self.run_to_main,
+        }
+    }
+
+    /// Collects the commands to run on startup. Source maps are applied first,
+    /// source files are executed before one-line commands of the same stage.
+    pub fn startup_commands(&self) -> StartupCommands {
+        let source = |files: &[String]| {
+            files
+                .iter()
+                .map(|file| format!("command source {}", debugger::quote_argument(file)))
+                .collect::<Vec<String>>()
+        };
+        let source_maps: Vec<String> = self
+            .source_map
+            .iter()
+            .map(|(old, new)| debugger::source_map_command(old, new))
+            .collect();
+        StartupCommands {
+            before_file: [
+                source_maps,
+                source(&self.source_before_file),
+                self.one_line_before_file.clone(),
+            ]
+            .concat(),
+            after_file: [source(&self.source), self.one_line.clone()].concat(),
+            on_crash: self.one_line_on_crash.clone(),
+        }
+    }
+}
+
+fn parse_source_map(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((old, new)) if !old.is_empty() && !new.is_empty() => {
+            Ok((old.to_string(), new.to_string()))
+        }
+        _ => Err("expected OLD=NEW".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_source_maps() {
+        assert_eq!(
+            parse_source_map("/build=/src"),
+            Ok(("/build".to_string(), "/src".to_string()))
+        );
+        // Only the first `=` separates the paths.
+        assert_eq!(
+            parse_source_map("/a=/b=c"),
+            Ok(("/a".to_string(), "/b=c".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_source_maps() {
+        assert!(parse_source_map("/build").is_err());
+        assert!(parse_source_map("=/src").is_err());
+        assert!(parse_source_map("/build=").is_err());
+    }
+}
@@ -1,34 +1,238 @@
-use anyhow::Result;
-use lldb::{LaunchFlags, SBAttachInfo, SBDebugger, SBProcess, SBTarget, SBThread, StateType};
+use std::ffi::{CStr, CString};
+use std::path::{Path, PathBuf};
 
-pub fn run(
+use anyhow::{bail, Result};
+use lldb::{
+    LanguageType, LaunchFlags, SBAttachInfo, SBBreakpoint, SBDebugger, SBExpressionOptions,
+    SBFrame, SBLaunchInfo, SBProcess, SBTarget, SBThread, SBValue, SBWatchpoint, StateType,
+    StopReason,
+};
+
+/// lldb commands to execute at the different stages of starting a debug session.
+#[derive(Default)]
+pub struct StartupCommands {
+    /// Executed before the target is created.
+    pub before_file: Vec<String>,
+    /// Executed after the target is created (and attached to). When the
+    /// target is picked on the start screen they are executed then.
+    pub after_file: Vec<String>,
+    /// Executed in batch mode if the process crashed.
+    pub on_crash: Vec<String>,
+}
+
+/// Options to launch a process with.
+#[derive(Clone, Default)]
+pub struct LaunchOptions {
+    pub args: Vec<String>,
+    pub working_dir: Option<String>,
+    /// Environment variables in the form `KEY=VALUE` added to the inherited environment.
+    pub env: Vec<String>,
+    /// Names of inherited environment variables to remove.
+    pub unset_env: Vec<String>,
+    pub stdin: Option<String>,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub disable_aslr: bool,
+    /// Run to `main` instead of stopping at the entry point of the dynamic loader.
+    pub run_to_main: bool,
+}
+
+/// Checks that an environment variable is given as `KEY=VALUE`.
+pub fn parse_env_entry(entry: &str) -> Result<String, String> {
+    match entry.split_once('=') {
+        Some((key, _)) if !key.is_empty() => Ok(entry.to_string()),
+        _ => Err(format!("expected KEY=VALUE: {}", entry)),
+    }
+}
+
+/// Creates the target to launch the executable with the options and executes
+/// the startup commands. The process is launched with `launch_target`, so the
+/// breakpoints of the saved session can be set before.
+pub fn create_target(
+    debugger: &SBDebugger,
     executable: &str,
-    source_init_files: bool,
-    args: Option<Vec<String>>,
+    options: &LaunchOptions,
+    commands: &[String],
+    output: &mut String,
 ) -> Result<SBTarget> {
-    let debugger = create_debugger(source_init_files);
     let target = debugger.create_target(executable, None, None, false)?;
-    let launch_info = target.get_launch_info();
-    launch_info.set_launch_flags(LaunchFlags::STOP_AT_ENTRY);
-    if let Some(args) = args {
-        launch_info.set_arguments(args.iter().map(AsRef::as_ref), false);
-    }
 
     // (ds): The launch info isn't persisted in the target if we don't
     //       explicitly set it here.
+    target.set_launch_info(launch_info(options));
+
+    execute_startup_commands(debugger, commands, output);
+
+    Ok(target)
+}
+
+/// Launches the process of a target from `create_target` unless a startup
+/// command did already. Also returns the id of the breakpoint on `main` if the
+/// process runs to it, see `launch_process`.
+pub fn launch_target(target: &SBTarget) -> Result<Option<i32>> {
+    // The startup commands might already have launched the process (`-o run`).
+    if target.process().is_valid() {
+        return Ok(None);
+    }
+    launch_process(target, target.get_launch_info())
+}
+
+/// Launches a new process for `target`, a still running process is killed first.
+/// Returns the id of the breakpoint on `main` like `launch_process`.
+pub fn launch(
+    target: &SBTarget,
+    options: &LaunchOptions,
+    enabled_watchpoints: &[i32],
+) -> Result<Option<i32>> {
+    let launch_info = launch_info(options);
     target.set_launch_info(launch_info.clone());
+    restart(target, enabled_watchpoints)
+}
+
+/// Kills the process if it's still running and launches it again with the
+/// launch info stored in the target. Breakpoints and watchpoints are kept.
+/// `enabled_watchpoints` are the watchpoints that were enabled while the
+/// process was last alive. Returns the id of the breakpoint on `main` like
+/// `launch_process`.
+pub fn restart(target: &SBTarget, enabled_watchpoints: &[i32]) -> Result<Option<i32>> {
+    // Watchpoints are disabled when the process goes away, remember which ones
+    // to enable again.
+    let process = target.process();
+    let enabled_before_kill = process_is_alive(&process).then(|| {
+        target
+            .watchpoints()
+            .filter(|wp| wp.is_enabled())
+            .map(|wp| wp.id())
+            .collect::<Vec<i32>>()
+    });
+    if enabled_before_kill.is_some() {
+        process.kill()?;
+    }
+    let existing: Vec<i32> = target.watchpoints().map(|wp| wp.id()).collect();
+    let enable = watchpoints_to_enable(
+        &existing,
+        enabled_before_kill.as_deref(),
+        enabled_watchpoints,
+    );
+
+    let run_to_main = launch_process(target, target.get_launch_info())?;
+
+    for watchpoint in target.watchpoints() {
+        if enable.contains(&watchpoint.id()) {
+            watchpoint.set_enabled(true);
+        }
+    }
+
+    Ok(run_to_main)
+}
+
+/// The watchpoints to enable after a restart: the ones enabled right before
+/// the process was killed, or the ones enabled while it was last alive if it
+/// was gone already. Deleted watchpoints are left out.
+fn watchpoints_to_enable(
+    existing: &[i32],
+    enabled_before_kill: Option<&[i32]>,
+    enabled_while_alive: &[i32],
+) -> Vec<i32> {
+    let enabled = enabled_before_kill.unwrap_or(enabled_while_alive);
+    existing
+        .iter()
+        .copied()
+        .filter(|id| enabled.contains(id))
+        .collect()
+}
+
+pub fn kill(process: &SBProcess) -> Result<()> {
+    process.kill()?;
+    Ok(())
+}
+
+/// Detaches from the process and lets it continue on its own.
+pub fn detach(process: &SBProcess) -> Result<()> {
+    process.detach()?;
+    Ok(())
+}
+
+/// Launches the process. Without stopping at the entry point it runs to
+/// `main`, the id of the breakpoint set for that is returned. It is an
+/// implementation detail that should be hidden from the user and deleted
+/// after the first stop, as it isn't deleted if `main` is never hit.
+fn launch_process(target: &SBTarget, launch_info: SBLaunchInfo) -> Result<Option<i32>> {
+    let run_to_main = !launch_info
+        .launch_flags()
+        .contains(LaunchFlags::STOP_AT_ENTRY);
+    let breakpoint = run_to_main.then(|| {
+        let breakpoint = target.breakpoint_create_by_name("main", None);
+        breakpoint.set_oneshot(true);
+        breakpoint.id()
+    });
 
     // Disable async events so the launch will be successful when we return from
     // the launch call and the launch will happen synchronously
+    let debugger = target.debugger();
     debugger.set_asynchronous(false);
-    target.launch(launch_info)?;
+    let result = target.launch(launch_info);
     debugger.set_asynchronous(true);
+    if let Err(err) = result {
+        if let Some(id) = breakpoint {
+            target.delete_breakpoint(id);
+        }
+        return Err(err.into());
+    }
 
-    Ok(target)
+    Ok(breakpoint)
 }
 
-pub fn attach_pid(pid: u64, source_init_files: bool) -> Result<SBTarget> {
-    let debugger = create_debugger(source_init_files);
+fn launch_info(options: &LaunchOptions) -> SBLaunchInfo {
+    let launch_info = SBLaunchInfo::new();
+
+    let mut flags = LaunchFlags::empty();
+    if !options.run_to_main {
+        flags |= LaunchFlags::STOP_AT_ENTRY;
+    }
+    if options.disable_aslr {
+        flags |= LaunchFlags::DISABLE_ASLR;
+    }
+    launch_info.set_launch_flags(flags);
+    launch_info.set_arguments(options.args.iter().map(AsRef::as_ref), false);
+
+    if let Some(working_dir) = &options.working_dir {
+        launch_info.set_working_directory(working_dir);
+    }
+
+    // A fresh launch info has an empty environment, the inherited environment
+    // has to be passed explicitly.
+    let overridden: Vec<&str> = options
+        .env
+        .iter()
+        .map(|entry| entry.split_once('=').map_or(entry.as_str(), |(key, _)| key))
+        .collect();
+    let env: Vec<String> = std::env::vars()
+        .filter(|(key, _)| !options.unset_env.contains(key) && !overridden.contains(&key.as_str()))
+        .map(|(key, value)| format!("{}={}", key, value))
+        .chain(options.env.iter().cloned())
+        .collect();
+    launch_info.set_environment_entries(env.iter().map(AsRef::as_ref), false);
+
+    for (fd, path, read, write) in [
+        (0, &options.stdin, true, false),
+        (1, &options.stdout, false, true),
+        (2, &options.stderr, false, true),
+    ] {
+        if let Some(path) = path {
+            launch_info.add_open_file_action(fd, path, read, write);
+        }
+    }
+
+    launch_info
+}
+
+pub fn attach_pid(
+    debugger: &SBDebugger,
+    pid: u64,
+    commands: &[String],
+    output: &mut String,
+) -> Result<SBTarget> {
     let target = debugger.create_target("", None, None, false)?;
     let attach_info = SBAttachInfo::new_with_pid(pid);
 
@@ -37,24 +241,305 @@ pub fn attach_pid(pid: u64, source_init_files: bool) -> Result<SBTarget> {
     debugger.set_asynchronous(false);
     target.attach(attach_info)?;
     debugger.set_asynchronous(true);
+    execute_startup_commands(debugger, commands, output);
 
     Ok(target)
 }
 
-pub fn attach_name(name: &str, source_init_files: bool) -> Result<SBTarget> {
-    let debugger = create_debugger(source_init_files);
+pub fn attach_name(
+    debugger: &SBDebugger,
+    name: &str,
+    wait_for: bool,
+    commands: &[String],
+    output: &mut String,
+) -> Result<SBTarget> {
     let target = debugger.create_target("", None, None, false)?;
-    let attach_info = SBAttachInfo::new_with_path(name, true, false);
+
+    if wait_for {
+        // Waiting for the process to launch can take forever. The attach
+        // returns immediately and the process stays in the attaching state
+        // until a matching process was found or `SBProcess::stop` is called.
+        let attach_info = SBAttachInfo::new_with_path(name, true, true);
+        target.attach(attach_info)?;
+        return Ok(target);
+    }
+
+    let attach_info = SBAttachInfo::new_with_path(name, false, false);
 
     // Disable async events so the attach will be successful when we return from
     // the attach call and the attach will happen synchronously
     debugger.set_asynchronous(false);
     target.attach(attach_info)?;
     debugger.set_asynchronous(true);
+    execute_startup_commands(debugger, commands, output);
+
+    Ok(target)
+}
+
+/// Connects to a gdb-server or lldb-server stub. The executable is optional
+/// and only used to find symbols.
+pub fn connect(
+    debugger: &SBDebugger,
+    executable: Option<&str>,
+    address: &str,
+    commands: &[String],
+    output: &mut String,
+) -> Result<SBTarget> {
+    let target = debugger.create_target(executable.unwrap_or_default(), None, None, false)?;
+    let url = if address.contains("://") {
+        address.to_string()
+    } else {
+        format!("connect://{}", address)
+    };
+
+    // Disable async events so the connect will be successful when we return from
+    // the connect call and the connect will happen synchronously
+    debugger.set_asynchronous(false);
+    let result = target.connect_remote(&debugger.listener(), &url, Some("gdb-remote"));
+    debugger.set_asynchronous(true);
+    result?;
+    execute_startup_commands(debugger, commands, output);
 
     Ok(target)
 }
 
+/// Selects and connects a remote platform served by `lldb-server platform`.
+/// Targets created afterwards launch and attach through it.
+pub fn connect_platform(debugger: &SBDebugger, platform: &str, url: &str) -> Result<()> {
+    for command in [
+        format!("platform select {}", platform),
+        format!("platform connect {}", url),
+    ] {
+        if let Err(err) = debugger.execute_command(&command) {
+            bail!("{} failed: {}", command, err.trim());
+        }
+    }
+    Ok(())
+}
+
+/// Makes lldb look for the source files under `old` in `new`.
+pub fn add_source_map(debugger: &SBDebugger, old: &str, new: &str) -> Result<()> {
+    let command = source_map_command(old, new);
+    if let Err(err) = debugger.execute_command(&command) {
+        bail!("{} failed: {}", command, err.trim());
+    }
+    Ok(())
+}
+
+pub fn source_map_command(old: &str, new: &str) -> String {
+    format!(
+        "settings append target.source-map {} {}",
+        quote_argument(old),
+        quote_argument(new)
+    )
+}
+
+/// Quotes an argument of an lldb command. Within double quotes lldb treats
+/// a backslash as escape character and expands backticks as expressions.
+pub fn quote_argument(argument: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in argument.chars() {
+        if matches!(c, '"' | '\\' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Creates a target without executable. It serves as a placeholder until a
+/// target was picked on the start screen.
+pub fn empty_target(debugger: &SBDebugger) -> Result<SBTarget> {
+    Ok(debugger.create_target("", None, None, false)?)
+}
+
+pub fn load_core(
+    debugger: &SBDebugger,
+    executable: &str,
+    core: &str,
+    commands: &[String],
+    output: &mut String,
+) -> Result<SBTarget> {
+    let target = debugger.create_target(executable, None, None, false)?;
+    target.load_core(core)?;
+    execute_startup_commands(debugger, commands, output);
+
+    Ok(target)
+}
+
+/// Executes an lldb command and appends the command and its result to `output`.
+pub fn execute_command(debugger: &SBDebugger, command: &str, output: &mut String) {
+    output.push_str(&format!("(lldb) {}\n", command));
+    match debugger.execute_command(command) {
+        Ok(result) => output.push_str(result),
+        Err(err) => output.push_str(&err),
+    }
+    output.push('\n');
+}
+
+/// Executes a command entered in the console like `execute_command`, but adds
+/// it to lldb's history. An empty command then repeats the previous one the
+/// way lldb does, e.g. `next` is repeated and `run` isn't.
+pub fn execute_console_command(debugger: &SBDebugger, command: &str, output: &mut String) {
+    output.push_str(&format!("(lldb) {}\n", command));
+    let command = match CString::new(command) {
+        Ok(command) => command,
+        Err(err) => {
+            output.push_str(&format!("{}\n", err));
+            return;
+        }
+    };
+    // lldb.rs always executes commands without adding them to the history.
+    unsafe {
+        let interpreter = lldb_sys::SBDebuggerGetCommandInterpreter(debugger.raw);
+        let result = lldb_sys::CreateSBCommandReturnObject();
+        lldb_sys::SBCommandInterpreterHandleCommand(interpreter, command.as_ptr(), result, true);
+        for text in [
+            lldb_sys::SBCommandReturnObjectGetOutput(result),
+            lldb_sys::SBCommandReturnObjectGetError(result),
+        ] {
+            if !text.is_null() {
+                output.push_str(&CStr::from_ptr(text).to_string_lossy());
+            }
+        }
+        lldb_sys::DisposeSBCommandReturnObject(result);
+        lldb_sys::DisposeSBCommandInterpreter(interpreter);
+    }
+    output.push('\n');
+}
+
+pub fn execute_startup_commands(debugger: &SBDebugger, commands: &[String], output: &mut String) {
+    if commands.is_empty() {
+        return;
+    }
+
+    // Disable async events so commands like `continue` have finished before
+    // the next command runs.
+    debugger.set_asynchronous(false);
+    for command in commands {
+        execute_command(debugger, command, output);
+    }
+    debugger.set_asynchronous(true);
+}
+
+/// Evaluates the expression in the context of the frame. This can take a long
+/// time, it should only be called from the worker thread.
+pub fn evaluate(frame: &SBFrame, expression: &str) -> Result<SBValue> {
+    let options = SBExpressionOptions::new();
+    let value = frame.evaluate_expression(expression, &options);
+    let error = value.error();
+    if error.is_failure() {
+        bail!("{}", error);
+    }
+    Ok(value)
+}
+
+/// Expands the `{expression}` placeholders of a logpoint message with the
+/// values of the expressions in the frame, `{{` and `}}` are literal braces.
+/// Evaluating can take a long time, it should only be called from the worker
+/// thread.
+pub fn format_log_message(frame: &SBFrame, template: &str) -> String {
+    expand_placeholders(template, |expression| match evaluate(frame, expression) {
+        Ok(value) => value
+            .value()
+            .or(value.summary())
+            .unwrap_or_default()
+            .to_string(),
+        Err(err) => format!("<{}>", err),
+    })
+}
+
+/// Replaces the `{expression}` placeholders of the template with what
+/// `evaluate` returns for them. An unterminated `{` is kept literally.
+fn expand_placeholders(template: &str, mut evaluate: impl FnMut(&str) -> String) -> String {
+    let mut message = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                message.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                message.push('}');
+            }
+            '{' => {
+                let mut expression = String::new();
+                let mut terminated = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        terminated = true;
+                        break;
+                    }
+                    expression.push(c);
+                }
+                if terminated {
+                    message.push_str(&evaluate(&expression));
+                } else {
+                    message.push('{');
+                    message.push_str(&expression);
+                }
+            }
+            c => message.push(c),
+        }
+    }
+    message
+}
+
+/// Returns the id of the breakpoint the thread stopped at.
+pub fn thread_breakpoint_hit(thread: &SBThread) -> Option<i32> {
+    matches!(thread.stop_reason(), StopReason::Breakpoint)
+        .then(|| thread.stop_reason_data_at_index(0) as i32)
+}
+
+/// Returns the threads of the process that stopped for a reason, e.g. at a
+/// breakpoint or because of a signal, and not just because another thread
+/// did.
+pub fn stopped_threads(process: &SBProcess) -> Vec<SBThread> {
+    process.threads().filter(thread_has_stop_reason).collect()
+}
+
+fn thread_has_stop_reason(thread: &SBThread) -> bool {
+    !matches!(thread.stop_reason(), StopReason::None | StopReason::Invalid)
+}
+
+/// Returns the id of the watchpoint a thread of the process stopped at.
+pub fn watchpoint_hit(process: &SBProcess) -> Option<i32> {
+    process.threads().find_map(|thread| {
+        matches!(thread.stop_reason(), StopReason::Watchpoint)
+            .then(|| thread.stop_reason_data_at_index(0) as i32)
+    })
+}
+
+pub fn read_memory(process: &SBProcess, address: u64, size: usize) -> Result<Vec<u8>> {
+    let mut buffer = vec![0; size];
+    process.read_memory(address, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Parses an address given as hex with a `0x` prefix or as decimal.
+pub fn parse_address(text: &str) -> Option<u64> {
+    match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Interrupts a running expression evaluation.
+pub fn interrupt(process: &SBProcess) {
+    process.send_async_interrupt();
+}
+
+pub fn executable_path(target: &SBTarget) -> Option<String> {
+    let executable = target.executable()?;
+    let path: PathBuf = [executable.directory(), executable.filename()]
+        .iter()
+        .collect();
+    Some(path.display().to_string())
+}
+
 pub fn initialize() {
     SBDebugger::initialize();
 }
@@ -63,40 +548,520 @@ pub fn terminate() {
     SBDebugger::terminate();
 }
 
-pub fn breakpoint_locations(target: &SBTarget) -> Vec<(i32, String, u32)> {
-    let mut locations = Vec::new();
-    for breakpoint in target.breakpoints() {
-        for location in breakpoint.locations() {
-            let Some(address) = location.address() else {
-                continue;
-            };
-            let Some(line_entry) = address.line_entry() else {
-                continue;
-            };
-            locations.push((
-                breakpoint.id(),
-                line_entry.filespec().filename().to_string(),
-                line_entry.line(),
-            ))
+/// Creates a breakpoint at the line of the file. The full path is used so it
+/// doesn't resolve in other files with the same name.
+pub fn create_breakpoint(target: &SBTarget, path: &Path, line: u32) -> SBBreakpoint {
+    target.breakpoint_create_by_location(&path.display().to_string(), line)
+}
+
+/// How a breakpoint is set, as added in the breakpoints panel or saved with
+/// the session.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum BreakpointSpec {
+    Line(PathBuf, u32),
+    Function(String),
+    /// A regular expression matching function and symbol names.
+    Regex(String),
+    /// A regular expression matching source lines in all files.
+    SourceRegex(String),
+    Address(u64),
+    CppThrow,
+    CppCatch,
+    RustPanic,
+}
+
+pub fn create_breakpoint_from_spec(
+    target: &SBTarget,
+    spec: &BreakpointSpec,
+) -> Result<SBBreakpoint> {
+    let breakpoint = match spec {
+        BreakpointSpec::Line(path, line) => create_breakpoint(target, path, *line),
+        BreakpointSpec::Function(name) => target.breakpoint_create_by_name(name, None),
+        BreakpointSpec::Regex(regex) => target.breakpoint_create_by_regex(regex, None),
+        // Without a file list all files are searched.
+        BreakpointSpec::SourceRegex(regex) => {
+            target.breakpoint_create_by_source_regex(regex, None, None)
+        }
+        BreakpointSpec::Address(address) => target.breakpoint_create_by_address(*address),
+        BreakpointSpec::CppThrow => {
+            target.breakpoint_create_for_exception(LanguageType::C_plus_plus, false, true)
+        }
+        BreakpointSpec::CppCatch => {
+            target.breakpoint_create_for_exception(LanguageType::C_plus_plus, true, false)
+        }
+        // Every panic of the Rust standard library goes through `rust_panic`.
+        BreakpointSpec::RustPanic => target.breakpoint_create_by_name("rust_panic", None),
+    };
+    if !breakpoint.is_valid() {
+        bail!("Failed to create breakpoint");
+    }
+    Ok(breakpoint)
+}
+
+/// The settings of a breakpoint that can be changed in the breakpoint editor.
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct BreakpointOptions {
+    pub enabled: bool,
+    /// Only stop if the expression evaluates to true.
+    pub condition: Option<String>,
+    /// Don't stop the first `ignore_count` times the breakpoint is hit.
+    pub ignore_count: u32,
+    /// Delete the breakpoint once it was hit.
+    pub one_shot: bool,
+    /// Only stop in the thread with the id, name or queue name.
+    pub thread_id: Option<u64>,
+    pub thread_name: Option<String>,
+    pub queue_name: Option<String>,
+}
+
+// Thread ids start at 1, 0 means any thread.
+const ANY_THREAD_ID: u64 = 0;
+
+pub fn breakpoint_options(breakpoint: &SBBreakpoint) -> BreakpointOptions {
+    BreakpointOptions {
+        enabled: breakpoint.is_enabled(),
+        condition: breakpoint.condition().map(String::from),
+        ignore_count: breakpoint.ignore_count(),
+        one_shot: breakpoint.is_oneshot(),
+        thread_id: Some(breakpoint.thread_id()).filter(|id| *id != ANY_THREAD_ID),
+        thread_name: breakpoint.thread_name().map(String::from),
+        queue_name: breakpoint.queue_name().map(String::from),
+    }
+}
+
+pub fn set_breakpoint_options(breakpoint: &SBBreakpoint, options: &BreakpointOptions) {
+    breakpoint.set_enabled(options.enabled);
+    breakpoint.set_condition(options.condition.as_deref().unwrap_or_default());
+    breakpoint.set_ignore_count(options.ignore_count);
+    breakpoint.set_oneshot(options.one_shot);
+    breakpoint.set_thread_id(options.thread_id.unwrap_or(ANY_THREAD_ID));
+    breakpoint.set_thread_name(options.thread_name.as_deref().unwrap_or_default());
+    breakpoint.set_queue_name(options.queue_name.as_deref().unwrap_or_default());
+}
+
+/// The language of the commands attached to a breakpoint.
+#[derive(Clone, Copy, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum ScriptLanguage {
+    #[default]
+    Lldb,
+    Python,
+}
+
+/// Commands to execute whenever a breakpoint is hit.
+#[derive(Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct BreakpointCommands {
+    pub language: ScriptLanguage,
+    /// lldb commands one per line, or the body of a Python function.
+    pub script: String,
+}
+
+/// Attaches the commands to the breakpoint, lldb executes them whenever it is
+/// hit. Python scripts become the body of the callback function, `frame` is
+/// the frame of the breakpoint. Empty commands remove the attached ones.
+pub fn set_breakpoint_commands(
+    breakpoint: &SBBreakpoint,
+    commands: &BreakpointCommands,
+) -> Result<()> {
+    // lldb.rs doesn't wrap setting the commands.
+    match commands.language {
+        // A Python callback can't be empty, replace it with no commands.
+        ScriptLanguage::Python if !commands.script.trim().is_empty() => {
+            let body = CString::new(commands.script.as_str())?;
+            unsafe {
+                let error =
+                    lldb_sys::SBBreakpointSetScriptCallbackBody(breakpoint.raw, body.as_ptr());
+                let message = lldb_sys::SBErrorFail(error).then(|| {
+                    CStr::from_ptr(lldb_sys::SBErrorGetCString(error))
+                        .to_string_lossy()
+                        .into_owned()
+                });
+                lldb_sys::DisposeSBError(error);
+                if let Some(message) = message {
+                    bail!("{}", message);
+                }
+            }
+        }
+        _ => {
+            let lines: Vec<CString> = commands
+                .script
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(CString::new)
+                .collect::<Result<_, _>>()?;
+            unsafe {
+                let list = lldb_sys::CreateSBStringList();
+                for line in &lines {
+                    lldb_sys::SBStringListAppendString(list, line.as_ptr());
+                }
+                lldb_sys::SBBreakpointSetCommandLineCommands(breakpoint.raw, list);
+                lldb_sys::DisposeSBStringList(list);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// What accesses of the watched memory trigger a watchpoint.
+#[derive(Clone, Copy, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum WatchKind {
+    Read,
+    #[default]
+    Write,
+    ReadWrite,
+}
+
+impl WatchKind {
+    pub const ALL: [WatchKind; 3] = [WatchKind::Read, WatchKind::Write, WatchKind::ReadWrite];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WatchKind::Read => "read",
+            WatchKind::Write => "write",
+            WatchKind::ReadWrite => "read/write",
+        }
+    }
+}
+
+/// The settings of a new watchpoint.
+pub struct WatchpointOptions {
+    pub size: usize,
+    pub kind: WatchKind,
+    /// Only stop if the expression evaluates to true.
+    pub condition: Option<String>,
+    pub ignore_count: u32,
+}
+
+pub fn create_watchpoint(
+    target: &SBTarget,
+    address: u64,
+    options: &WatchpointOptions,
+) -> Result<SBWatchpoint> {
+    let read = matches!(options.kind, WatchKind::Read | WatchKind::ReadWrite);
+    let write = matches!(options.kind, WatchKind::Write | WatchKind::ReadWrite);
+    let watchpoint = target.watch_address(address, options.size, read, write)?;
+    if let Some(condition) = &options.condition {
+        watchpoint.set_condition(condition);
+    }
+    watchpoint.set_ignore_count(options.ignore_count);
+    Ok(watchpoint)
+}
+
+/// Evaluates the expression and returns the address, size and type name of
+/// its value to watch it.
+pub fn watched_value(frame: &SBFrame, expression: &str) -> Result<(u64, usize, String)> {
+    let value = evaluate(frame, expression)?;
+    let Some(address) = value.load_address() else {
+        bail!("{} is not in memory", expression);
+    };
+    let type_name = value.display_type_name().unwrap_or_default().to_string();
+    Ok((address, value.byte_size(), type_name))
+}
+
+/// Returns how the breakpoint was set as (kind, spec), e.g.
+/// `("line", "file = '/src/main.c', line = 5, exact_match = 0")`.
+pub fn breakpoint_spec(breakpoint: &SBBreakpoint) -> (&'static str, String) {
+    // lldb.rs only exposes the description of the breakpoint through Debug:
+    // "SBBreakpoint: id = 1, <resolver description>, locations = 1".
+    describe_breakpoint(&format!("{:?}", breakpoint))
+}
+
+fn describe_breakpoint(description: &str) -> (&'static str, String) {
+    let spec = description
+        .split_once("id = ")
+        .and_then(|(_, rest)| rest.split_once(", "))
+        .map(|(_, spec)| spec)
+        .unwrap_or(description);
+    let spec = spec
+        .rsplit_once(", locations = ")
+        .map(|(spec, _)| spec)
+        .unwrap_or(spec)
+        .trim_end_matches([' ', '}'])
+        .to_string();
+
+    let kind = if spec.starts_with("file = ") {
+        "line"
+    } else if spec.starts_with("name = ") || spec.starts_with("names = ") {
+        "function"
+    } else if spec.starts_with("regex = ") {
+        "regex"
+    } else if spec.starts_with("source regex = ") {
+        "source regex"
+    } else if spec.starts_with("address = ") {
+        "address"
+    } else if spec.starts_with("Exception breakpoint") {
+        "exception"
+    } else {
+        "other"
+    };
+    (kind, spec)
+}
+
+/// Returns the spec to create the breakpoint again from lldb's description
+/// of it, for breakpoints the app didn't create itself, e.g. with a console
+/// command. `None` if the description isn't understood.
+pub fn parse_breakpoint_spec(breakpoint: &SBBreakpoint) -> Option<BreakpointSpec> {
+    let (kind, spec) = breakpoint_spec(breakpoint);
+    parse_spec(kind, &spec)
+}
+
+fn parse_spec(kind: &str, spec: &str) -> Option<BreakpointSpec> {
+    // The value of `key = 'value'` or `key = "value"`. lldb doesn't escape
+    // the quotes in the value, so it ends at the last one.
+    fn quoted<'a>(spec: &'a str, key: &str) -> Option<&'a str> {
+        let rest = spec.strip_prefix(key)?.strip_prefix(" = ")?;
+        let quote = rest.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+        let (value, _) = rest[1..].rsplit_once(quote)?;
+        Some(value)
+    }
+
+    match kind {
+        "line" => {
+            // The path may contain quotes and commas, the line follows the
+            // last `', line = `.
+            let (path, rest) = spec.strip_prefix("file = '")?.rsplit_once("', line = ")?;
+            let line = rest.split(',').next()?.trim().parse().ok()?;
+            Some(BreakpointSpec::Line(PathBuf::from(path), line))
+        }
+        // Breakpoints on several names aren't supported.
+        "function" => quoted(spec, "name").map(|name| BreakpointSpec::Function(name.into())),
+        "regex" => quoted(spec, "regex").map(|regex| BreakpointSpec::Regex(regex.into())),
+        "source regex" => {
+            quoted(spec, "source regex").map(|regex| BreakpointSpec::SourceRegex(regex.into()))
+        }
+        "address" => {
+            let hex = spec.split_once("0x")?.1;
+            let end = hex
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .unwrap_or(hex.len());
+            u64::from_str_radix(&hex[..end], 16)
+                .ok()
+                .map(BreakpointSpec::Address)
+        }
+        "exception" if spec.contains("throw: on") => Some(BreakpointSpec::CppThrow),
+        "exception" if spec.contains("catch: on") => Some(BreakpointSpec::CppCatch),
+        _ => None,
+    }
+}
+
+pub fn process_is_alive(process: &SBProcess) -> bool {
+    process.is_valid() && state_is_alive(process.state())
+}
+
+pub fn state_is_alive(state: StateType) -> bool {
+    !matches!(
+        state,
+        StateType::Invalid | StateType::Unloaded | StateType::Detached | StateType::Exited
+    )
+}
+
+pub fn state_can_stop(state: StateType) -> bool {
+    matches!(state, StateType::Running | StateType::Stepping)
+}
+
+pub fn state_can_continue(state: StateType) -> bool {
+    matches!(state, StateType::Stopped | StateType::Suspended)
+}
+
+pub fn process_crashed(process: &SBProcess) -> bool {
+    process.is_stopped()
+        && process
+            .threads()
+            .any(|thread| thread_crashed(process, &thread))
+}
+
+fn thread_crashed(process: &SBProcess, thread: &SBThread) -> bool {
+    match thread.stop_reason() {
+        StopReason::Exception => true,
+        // The debugger uses these signals itself to stop the process.
+        StopReason::Signal => !matches!(
+            signal_name(process, thread.stop_reason_data_at_index(0)).as_deref(),
+            Some("SIGSTOP" | "SIGTRAP")
+        ),
+        _ => false,
+    }
+}
+
+/// How the process exited.
+#[derive(Clone, Debug)]
+pub struct ProcessExit {
+    pub status: i32,
+    /// lldb's description, e.g. `Terminated due to signal 9`.
+    pub description: Option<String>,
+    /// The number and name of the signal that terminated the process.
+    pub signal: Option<(u64, Option<String>)>,
+}
+
+impl ProcessExit {
+    pub fn new(process: &SBProcess) -> Self {
+        let description = process
+            .exit_description()
+            .filter(|description| !description.is_empty())
+            .map(String::from);
+        let signal = description
+            .as_deref()
+            .and_then(|description| description.split_once("signal "))
+            .and_then(|(_, number)| number.trim().parse().ok())
+            .map(|number| (number, signal_name(process, number)));
+        Self {
+            status: process.exit_status(),
+            description,
+            signal,
+        }
+    }
+
+    /// The exit code, or the signal that terminated the process.
+    pub fn summary(&self) -> String {
+        match &self.signal {
+            Some((number, name)) => format!(
+                "terminated by {} ({})",
+                name.as_deref().unwrap_or("signal"),
+                number
+            ),
+            None => format!("exited with status {}", self.status),
         }
     }
-    locations
 }
 
-pub fn process_can_stop(process: &SBProcess) -> bool {
-    matches!(process.state(), StateType::Running | StateType::Stepping)
+/// Why the process stopped, as shown in the stop banner.
+pub struct StopInfo {
+    /// The thread that stopped for the reason.
+    pub thread: SBThread,
+    /// lldb's description, e.g. `signal SIGSEGV: invalid address (fault address: 0x0)`.
+    pub description: String,
+    pub cause: StopCause,
+    pub crashed: bool,
+}
+
+pub enum StopCause {
+    /// Breakpoint and location id.
+    Breakpoint(i32, i32),
+    Watchpoint(i32),
+    Signal {
+        number: u64,
+        /// The name of the signal on the target, the numbers differ between
+        /// platforms.
+        name: Option<String>,
+        /// lldb's description of the signal code, e.g. `invalid address`.
+        code: Option<String>,
+        /// The faulting address of SIGSEGV and SIGBUS and the memory region it
+        /// falls in.
+        fault_address: Option<(u64, String)>,
+    },
+    Exception,
+    /// Stepping finished or the process was interrupted.
+    Other,
+}
+
+/// Returns why the process stopped. Threads that crashed take precedence
+/// over the selected thread.
+pub fn stop_info(process: &SBProcess) -> Option<StopInfo> {
+    let selected_thread = process.selected_thread();
+    let thread = process
+        .threads()
+        .find(|thread| thread_crashed(process, thread))
+        .or_else(|| thread_has_stop_reason(&selected_thread).then_some(selected_thread))
+        .or_else(|| process.threads().find(thread_has_stop_reason))?;
+
+    let description = thread.stop_description();
+    let data = |index| thread.stop_reason_data_at_index(index);
+    let cause = match thread.stop_reason() {
+        StopReason::Breakpoint => StopCause::Breakpoint(data(0) as i32, data(1) as i32),
+        StopReason::Watchpoint => StopCause::Watchpoint(data(0) as i32),
+        StopReason::Signal => {
+            let number = data(0);
+            let name = signal_name(process, number);
+            let fault_address = matches!(name.as_deref(), Some("SIGSEGV" | "SIGBUS"))
+                .then(|| fault_address(&description))
+                .flatten()
+                .map(|address| (address, memory_region(process, address)));
+            StopCause::Signal {
+                number,
+                name,
+                code: signal_code(&description),
+                fault_address,
+            }
+        }
+        StopReason::Exception => StopCause::Exception,
+        _ => StopCause::Other,
+    };
+    Some(StopInfo {
+        crashed: thread_crashed(process, &thread),
+        thread,
+        description,
+        cause,
+    })
+}
+
+// lldb describes the signal code after the name of the signal, e.g.
+// `signal SIGSEGV: invalid address (fault address: 0x10)`.
+fn signal_code(description: &str) -> Option<String> {
+    let code = description.split_once(": ")?.1;
+    let code = code
+        .split_once(" (fault address: ")
+        .map(|(code, _)| code)
+        .unwrap_or(code)
+        .trim();
+    (!code.is_empty()).then(|| code.to_string())
+}
+
+// lldb puts the faulting address in the description of the stop, e.g.
+// `signal SIGSEGV: invalid address (fault address: 0x10)`.
+fn fault_address(description: &str) -> Option<u64> {
+    let hex = description.split_once("fault address: 0x")?.1;
+    let end = hex
+        .find(|c: char| !c.is_ascii_hexdigit())
+        .unwrap_or(hex.len());
+    u64::from_str_radix(&hex[..end], 16).ok()
 }
 
-pub fn process_can_continue(process: &SBProcess) -> bool {
-    matches!(process.state(), StateType::Stopped | StateType::Suspended)
+/// Describes the memory region the address falls in, e.g.
+/// `[0x1000-0x2000) r-x /usr/lib/libc.so.6`.
+pub fn memory_region(process: &SBProcess, address: u64) -> String {
+    let region = match process.get_memory_region_info(address) {
+        Ok(region) => region,
+        Err(err) => return format!("unknown region: {}", err),
+    };
+    if !region.is_mapped() {
+        return "not mapped".to_string();
+    }
+    let permissions: String = [
+        (region.is_readable(), 'r'),
+        (region.is_writable(), 'w'),
+        (region.is_executable(), 'x'),
+    ]
+    .iter()
+    .map(|(allowed, c)| if *allowed { *c } else { '-' })
+    .collect();
+    format!(
+        "[{:#x}-{:#x}) {} {}",
+        region.region_base(),
+        region.region_end(),
+        permissions,
+        region.name().unwrap_or_default()
+    )
 }
 
-pub fn process_frame_has_parent(process: &SBProcess) -> bool {
-    let frame = process.selected_thread().selected_frame();
-    frame.is_valid() && frame.parent_frame().is_some()
+/// Returns the name of the signal on the platform of the process, e.g. SIGBUS
+/// is 7 on Linux and 10 on macOS.
+pub fn signal_name(process: &SBProcess, number: u64) -> Option<String> {
+    // lldb.rs doesn't wrap SBUnixSignals.
+    unsafe {
+        let signals = lldb_sys::SBProcessGetUnixSignals(process.raw);
+        let name = lldb_sys::SBUnixSignalsGetSignalAsCString(signals, number as i32);
+        let name = (!name.is_null()).then(|| CStr::from_ptr(name).to_string_lossy().into_owned());
+        lldb_sys::DisposeSBUnixSignals(signals);
+        name
+    }
 }
 
-fn create_debugger(source_init_files: bool) -> SBDebugger {
+/// The first frame of the thread with source, where a crash is most likely
+/// to be understood.
+pub fn first_frame_with_source(thread: &SBThread) -> Option<SBFrame> {
+    thread.frames().find(|frame| frame.line_entry().is_some())
+}
+
+pub fn create_debugger(source_init_files: bool) -> SBDebugger {
     SBDebugger::initialize();
 
     let debugger = SBDebugger::create(source_init_files);
@@ -109,3 +1074,113 @@ fn create_debugger(source_init_files: bool) -> SBDebugger {
 
     debugger
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(description: &str) -> Option<BreakpointSpec> {
+        let (kind, spec) = describe_breakpoint(description);
+        parse_spec(kind, &spec)
+    }
+
+    #[test]
+    fn parses_line_breakpoints() {
+        assert_eq!(
+            parse(
+                "SBBreakpoint: id = 1, file = '/src/main.c', line = 5, exact_match = 0, \
+                 locations = 1"
+            ),
+            Some(BreakpointSpec::Line("/src/main.c".into(), 5))
+        );
+        assert_eq!(
+            parse("SBBreakpoint: id = 2, file = '/src/it's here.c', line = 7, exact_match = 0"),
+            Some(BreakpointSpec::Line("/src/it's here.c".into(), 7))
+        );
+        assert_eq!(
+            parse(
+                "SBBreakpoint: id = 3, file = '/src/a, line = 1/b.c', line = 9, column = 2, \
+                 exact_match = 0, locations = 0 (pending)"
+            ),
+            Some(BreakpointSpec::Line("/src/a, line = 1/b.c".into(), 9))
+        );
+    }
+
+    #[test]
+    fn parses_other_breakpoints() {
+        assert_eq!(
+            parse("SBBreakpoint: id = 1, name = 'main', locations = 1"),
+            Some(BreakpointSpec::Function("main".into()))
+        );
+        assert_eq!(
+            parse("SBBreakpoint: id = 2, regex = 'it's_.*', locations = 3"),
+            Some(BreakpointSpec::Regex("it's_.*".into()))
+        );
+        assert_eq!(
+            parse("SBBreakpoint: id = 3, source regex = \"TODO\", locations = 0"),
+            Some(BreakpointSpec::SourceRegex("TODO".into()))
+        );
+        assert_eq!(
+            parse("SBBreakpoint: id = 4, address = 0x0000000100003f50, locations = 1"),
+            Some(BreakpointSpec::Address(0x100003f50))
+        );
+        assert_eq!(
+            parse(
+                "SBBreakpoint: id = 5, Exception breakpoint (catch: off throw: on), locations = 1"
+            ),
+            Some(BreakpointSpec::CppThrow)
+        );
+    }
+
+    #[test]
+    fn expands_placeholders() {
+        let expand =
+            |template| expand_placeholders(template, |expression| expression.to_uppercase());
+        assert_eq!(expand("i = {i}, j = {j}"), "i = I, j = J");
+        assert_eq!(expand("{{i}} {{{i}}}"), "{i} {I}");
+        assert_eq!(expand("a }b"), "a }b");
+    }
+
+    #[test]
+    fn keeps_unterminated_placeholders() {
+        let expand = |template| expand_placeholders(template, |_| "?".to_string());
+        assert_eq!(expand("x = {x"), "x = {x");
+        assert_eq!(expand("x = {"), "x = {");
+        assert_eq!(expand("{x} {y"), "? {y");
+    }
+
+    #[test]
+    fn parses_signal_codes() {
+        let description = "signal SIGSEGV: invalid address (fault address: 0x10)";
+        assert_eq!(signal_code(description).as_deref(), Some("invalid address"));
+        assert_eq!(fault_address(description), Some(0x10));
+        assert_eq!(signal_code("signal SIGABRT"), None);
+    }
+
+    #[test]
+    fn enables_the_watchpoints_enabled_before_the_kill() {
+        assert_eq!(
+            watchpoints_to_enable(&[1, 2, 3], Some(&[1, 3]), &[2]),
+            [1, 3]
+        );
+        assert!(watchpoints_to_enable(&[1, 2], Some(&[]), &[1, 2]).is_empty());
+    }
+
+    #[test]
+    fn enables_the_watchpoints_enabled_while_alive_after_an_exit() {
+        assert_eq!(watchpoints_to_enable(&[1, 2, 3], None, &[3, 1]), [1, 3]);
+        // Deleted since the process exited.
+        assert_eq!(watchpoints_to_enable(&[2], None, &[1, 2]), [2]);
+        assert!(watchpoints_to_enable(&[], None, &[1]).is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_breakpoints() {
+        assert_eq!(
+            parse("SBBreakpoint: id = 1, names = {'a', 'b'}, locations = 2"),
+            None
+        );
+        assert_eq!(parse("SBBreakpoint: id = 2, file = '/src/main.c'"), None);
+        assert_eq!(parse("something else"), None);
+    }
+}
@@ -0,0 +1,203 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::{thread, thread::JoinHandle};
+
+use egui::Context;
+use lldb::{
+    BreakpointEventType, SBEvent, SBListener, SBProcess, SBTarget, SBThread, StateType,
+    WatchpointEventType,
+};
+
+use crate::debugger::ProcessExit;
+
+// Event type bits of the process, target and thread broadcasters.
+// See lldb/API/SBProcess.h, lldb/API/SBTarget.h and lldb/API/SBThread.h.
+const PROCESS_STATE_CHANGED: u32 = 1 << 0;
+const PROCESS_STDOUT: u32 = 1 << 2;
+const PROCESS_STDERR: u32 = 1 << 3;
+const TARGET_BREAKPOINT_CHANGED: u32 = 1 << 0;
+const TARGET_MODULES_LOADED: u32 = 1 << 1;
+const TARGET_MODULES_UNLOADED: u32 = 1 << 2;
+const TARGET_WATCHPOINT_CHANGED: u32 = 1 << 3;
+const THREAD_SELECTED_FRAME_CHANGED: u32 = 1 << 3;
+const THREAD_SELECTED: u32 = 1 << 4;
+
+/// How a breakpoint or watchpoint changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Added,
+    Removed,
+    Resolved,
+    Modified,
+}
+
+impl Change {
+    pub fn label(self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Resolved => "resolved",
+            Change::Modified => "modified",
+        }
+    }
+}
+
+/// The debugger events the UI reacts to.
+#[derive(Debug, Clone)]
+pub enum DebuggerEvent {
+    /// The process changed its state, `exit` is set once it exited.
+    ProcessStateChanged {
+        state: StateType,
+        exit: Option<ProcessExit>,
+    },
+    StdoutAvailable,
+    StderrAvailable,
+    BreakpointChanged {
+        id: i32,
+        change: Change,
+    },
+    WatchpointChanged {
+        id: i32,
+        change: Change,
+    },
+    ModulesLoaded,
+    ModulesUnloaded,
+    ThreadSelectionChanged,
+}
+
+/// Receives the events of the debugger listener on a background thread and
+/// forwards them as `DebuggerEvent`s.
+pub struct EventThread {
+    handle: Option<JoinHandle<()>>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl EventThread {
+    /// Spawns the thread. The UI is repainted after each event so it can
+    /// update without user interaction.
+    pub fn spawn(egui_ctx: Context, listener: SBListener) -> (Self, Receiver<DebuggerEvent>) {
+        let (sender, receiver) = channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let shutdown = shutdown.clone();
+            thread::spawn(move || run(egui_ctx, listener, sender, shutdown))
+        };
+        (
+            Self {
+                handle: Some(handle),
+                shutdown,
+            },
+            receiver,
+        )
+    }
+
+    /// Stops the thread and waits for it to finish.
+    pub fn shutdown(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                tracing::error!("LLDB event thread panicked");
+            }
+        }
+    }
+}
+
+fn run(
+    egui_ctx: Context,
+    listener: SBListener,
+    sender: Sender<DebuggerEvent>,
+    shutdown: Arc<AtomicBool>,
+) {
+    let event = SBEvent::new();
+    // Wake up every second to check if we should shut down.
+    while !shutdown.load(Ordering::Relaxed) {
+        listener.wait_for_event(1, &event);
+        if !event.is_valid() {
+            continue;
+        }
+        tracing::debug!("LLDB event: {:?}", event);
+        for debugger_event in decode(&event) {
+            if sender.send(debugger_event).is_err() {
+                // The receiving app is gone.
+                return;
+            }
+        }
+        egui_ctx.request_repaint();
+    }
+}
+
+fn decode(event: &SBEvent) -> Vec<DebuggerEvent> {
+    let mut events = Vec::new();
+    let event_type = event.event_type();
+    let broadcaster_class = event.broadcaster_class();
+
+    if broadcaster_class == SBProcess::broadcaster_class_name() {
+        if event_type & PROCESS_STATE_CHANGED != 0 {
+            if let Some(process_event) = event.as_process_event() {
+                let state = process_event.process_state();
+                // Read here so the UI thread doesn't have to wait for LLDB.
+                let exit = matches!(state, StateType::Exited)
+                    .then(|| ProcessExit::new(&process_event.process()));
+                events.push(DebuggerEvent::ProcessStateChanged { state, exit });
+            }
+        }
+        if event_type & PROCESS_STDOUT != 0 {
+            events.push(DebuggerEvent::StdoutAvailable);
+        }
+        if event_type & PROCESS_STDERR != 0 {
+            events.push(DebuggerEvent::StderrAvailable);
+        }
+    } else if broadcaster_class == SBTarget::broadcaster_class_name() {
+        if event_type & TARGET_BREAKPOINT_CHANGED != 0 {
+            if let Some(breakpoint_event) = event.as_breakpoint_event() {
+                let event_type = breakpoint_event.breakpoint_event_type();
+                let change = if event_type.contains(BreakpointEventType::ADDED) {
+                    Change::Added
+                } else if event_type.contains(BreakpointEventType::REMOVED) {
+                    Change::Removed
+                } else if event_type.intersects(
+                    BreakpointEventType::LOCATIONS_ADDED
+                        | BreakpointEventType::LOCATIONS_REMOVED
+                        | BreakpointEventType::LOCATIONS_RESOLVED,
+                ) {
+                    Change::Resolved
+                } else {
+                    Change::Modified
+                };
+                events.push(DebuggerEvent::BreakpointChanged {
+                    id: breakpoint_event.breakpoint().id(),
+                    change,
+                });
+            }
+        }
+        if event_type & TARGET_WATCHPOINT_CHANGED != 0 {
+            if let Some(watchpoint_event) = event.as_watchpoint_event() {
+                let event_type = watchpoint_event.watchpoint_event_type();
+                let change = if event_type.contains(WatchpointEventType::ADDED) {
+                    Change::Added
+                } else if event_type.contains(WatchpointEventType::REMOVED) {
+                    Change::Removed
+                } else {
+                    Change::Modified
+                };
+                events.push(DebuggerEvent::WatchpointChanged {
+                    id: watchpoint_event.watchpoint().id(),
+                    change,
+                });
+            }
+        }
+        if event_type & TARGET_MODULES_LOADED != 0 {
+            events.push(DebuggerEvent::ModulesLoaded);
+        }
+        if event_type & TARGET_MODULES_UNLOADED != 0 {
+            events.push(DebuggerEvent::ModulesUnloaded);
+        }
+    } else if broadcaster_class == SBThread::broadcaster_class_name()
+        && event_type & (THREAD_SELECTED | THREAD_SELECTED_FRAME_CHANGED) != 0
+    {
+        events.push(DebuggerEvent::ThreadSelectionChanged);
+    }
+
+    events
+}
@@ -5,9 +5,12 @@ mod app;
 mod cli;
 mod debugger;
 mod defines;
+mod events;
+mod processes;
 mod resources;
+mod shell_words;
 
-use anyhow::{bail, Result};
+use anyhow::Result;
 use clap::Parser;
 
 use crate::cli::Cli;
@@ -22,17 +25,103 @@ fn main() -> Result<()> {
 
     debugger::initialize();
 
-    let target = if let Some(executable) = cli.executable {
-        debugger::run(&executable, !cli.no_lldbinit, cli.args)?
+    let commands = cli.startup_commands();
+    let launch_options = cli.launch_options();
+    let mut console_output = String::new();
+
+    let debugger = debugger::create_debugger(!cli.no_lldbinit);
+    debugger::execute_startup_commands(&debugger, &commands.before_file, &mut console_output);
+
+    if let (Some(platform), Some(url)) = (&cli.platform, &cli.platform_url) {
+        debugger::connect_platform(&debugger, platform, url)?;
+    }
+
+    let target = if let (Some(executable), Some(core)) = (&cli.executable, &cli.core) {
+        debugger::load_core(
+            &debugger,
+            executable,
+            core,
+            &commands.after_file,
+            &mut console_output,
+        )?
+    } else if let Some(address) = &cli.connect {
+        debugger::connect(
+            &debugger,
+            cli.executable.as_deref(),
+            address,
+            &commands.after_file,
+            &mut console_output,
+        )?
+    } else if let Some(executable) = &cli.executable {
+        debugger::create_target(
+            &debugger,
+            executable,
+            &launch_options,
+            &commands.after_file,
+            &mut console_output,
+        )?
     } else if let Some(pid) = cli.attach_pid {
-        debugger::attach_pid(pid, !cli.no_lldbinit)?
-    } else if let Some(name) = cli.attach_name {
-        debugger::attach_name(&name, !cli.no_lldbinit)?
+        debugger::attach_pid(&debugger, pid, &commands.after_file, &mut console_output)?
+    } else if let Some(name) = &cli.attach_name {
+        debugger::attach_name(
+            &debugger,
+            name,
+            cli.wait_for,
+            &commands.after_file,
+            &mut console_output,
+        )?
+    } else {
+        // No target given, it will be picked on the start screen.
+        debugger::empty_target(&debugger)?
+    };
+    let start_screen = cli.executable.is_none()
+        && cli.attach_pid.is_none()
+        && cli.attach_name.is_none()
+        && cli.connect.is_none();
+
+    // Only a launched process can be launched again.
+    let launch = cli.core.is_none() && cli.connect.is_none() && cli.executable.is_some();
+    let launch_options = launch.then_some(launch_options);
+
+    let remote = if let Some(address) = &cli.connect {
+        Some(format!("gdb-remote {}", address))
     } else {
-        // Should not happen because we require at least one parameter as cli option.
-        bail!("required debug session parameter missing")
+        cli.platform
+            .as_ref()
+            .zip(cli.platform_url.as_ref())
+            .map(|(platform, url)| format!("{} {}", platform, url))
     };
 
+    // The attach has not finished yet or the target has not been picked, the
+    // commands have to wait until it did.
+    let pending_commands = if cli.wait_for || start_screen {
+        commands.after_file.clone()
+    } else {
+        Vec::new()
+    };
+
+    // The UI launches the process once it restored the saved session, in
+    // batch mode it is only shown if the process crashed.
+    let mut run_to_main_breakpoint = None;
+    if cli.batch && launch {
+        run_to_main_breakpoint = debugger::launch_target(&target)?;
+    }
+
+    if cli.batch {
+        // Like lldb we only stay around if the process crashed.
+        if !debugger::process_crashed(&target.process()) {
+            print!("{}", console_output);
+            let _ = target.process().kill();
+            debugger::terminate();
+            return Ok(());
+        }
+        debugger::execute_startup_commands(
+            &target.debugger(),
+            &commands.on_crash,
+            &mut console_output,
+        );
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_app_id(format!(
@@ -49,7 +138,22 @@ fn main() -> Result<()> {
     eframe::run_native(
         crate::defines::APP_NAME,
         options,
-        Box::new(|cc| Box::new(app::App::new(cc, target))),
+        Box::new(|cc| {
+            Box::new(app::App::new(
+                cc,
+                app::Session {
+                    target,
+                    launch_options,
+                    run_to_main_breakpoint,
+                    launch: launch && !cli.batch,
+                    core_file: cli.core,
+                    remote,
+                    console_output,
+                    pending_commands,
+                    start_screen,
+                },
+            ))
+        }),
     )
     .unwrap();
 
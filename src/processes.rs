@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::fs::{read_dir, read_to_string};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// The kernel reports process start times in clock ticks, which are always
+// exposed as USER_HZ = 100 to userspace.
+const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+/// A local process as read from `/proc`.
+#[derive(Debug, Clone)]
+pub struct Process {
+    pub pid: u64,
+    pub user: String,
+    pub command_line: String,
+    pub start_time: Option<SystemTime>,
+}
+
+/// Lists the local processes. Returns an empty list on systems without `/proc`.
+pub fn list() -> Vec<Process> {
+    let Ok(entries) = read_dir("/proc") else {
+        return Vec::new();
+    };
+    let users = users();
+    let boot_time = boot_time();
+
+    let mut processes: Vec<Process> = entries
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u64>().ok())
+        .filter_map(|pid| {
+            let status = read_to_string(format!("/proc/{}/status", pid)).ok()?;
+            let uid = status
+                .lines()
+                .find_map(|line| line.strip_prefix("Uid:"))
+                .and_then(|uids| uids.split_whitespace().next())?;
+            let user = users.get(uid).cloned().unwrap_or_else(|| uid.to_string());
+
+            // Arguments are separated by NUL bytes, kernel threads don't have any.
+            let mut command_line = read_to_string(format!("/proc/{}/cmdline", pid))
+                .ok()?
+                .trim_end_matches('\0')
+                .replace('\0', " ");
+            if command_line.is_empty() {
+                let name = status
+                    .lines()
+                    .find_map(|line| line.strip_prefix("Name:"))?
+                    .trim();
+                command_line = format!("[{}]", name);
+            }
+
+            let start_time = boot_time.zip(start_ticks(pid)).map(|(boot_time, ticks)| {
+                boot_time + Duration::from_millis(ticks * 1000 / CLOCK_TICKS_PER_SECOND)
+            });
+
+            Some(Process {
+                pid,
+                user,
+                command_line,
+                start_time,
+            })
+        })
+        .collect();
+    processes.sort_by_key(|process| process.pid);
+    processes
+}
+
+// Maps user ids to user names.
+fn users() -> HashMap<String, String> {
+    read_to_string("/etc/passwd")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?;
+            Some((uid.to_string(), name.to_string()))
+        })
+        .collect()
+}
+
+fn boot_time() -> Option<SystemTime> {
+    let stat = read_to_string("/proc/stat").ok()?;
+    let seconds = stat
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+// Start time of the process in clock ticks after boot.
+fn start_ticks(pid: u64) -> Option<u64> {
+    let stat = read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name in parentheses may contain spaces, the fields after it
+    // don't. starttime is the 22nd field, the 20th after the command name.
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
@@ -0,0 +1,113 @@
+//! Splitting and joining of program arguments the way a POSIX shell does, so
+//! arguments with spaces can be edited as one line.
+
+/// Splits the line into arguments. Single quotes keep everything literally,
+/// in double quotes and outside of quotes a backslash escapes the next
+/// character.
+pub fn split(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    // The argument being parsed, `None` between arguments.
+    let mut arg: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                args.extend(arg.take());
+            }
+            '\'' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => return Err("unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => arg.push(c),
+                            Some(c) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => return Err("unterminated double quote".to_string()),
+                        },
+                        Some(c) => arg.push(c),
+                        None => return Err("unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => arg.get_or_insert_with(String::new).push(c),
+                None => return Err("trailing backslash".to_string()),
+            },
+            c => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(arg);
+    Ok(args)
+}
+
+/// Joins the arguments into a line that `split` turns into the same
+/// arguments again.
+pub fn join(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| quote(arg))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn quote(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,@+%".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(split("  -a  b\tc ").unwrap(), ["-a", "b", "c"]);
+        assert!(split("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn splits_quoted_arguments() {
+        assert_eq!(
+            split(r#"'a b' "c \"d\" \n" e\ f ''"#).unwrap(),
+            ["a b", r#"c "d" \n"#, "e f", ""]
+        );
+        assert_eq!(split("--name='x y'").unwrap(), ["--name=x y"]);
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes() {
+        assert!(split("'a").is_err());
+        assert!(split("\"a").is_err());
+        assert!(split("a\\").is_err());
+    }
+
+    #[test]
+    fn join_round_trips() {
+        let args = [
+            "plain",
+            "with space",
+            "it's",
+            "",
+            "\"quoted\"",
+            "back\\slash",
+        ]
+        .map(String::from);
+        assert_eq!(split(&join(&args)).unwrap(), args);
+        assert_eq!(join(&args[..2]), "plain 'with space'");
+    }
+}